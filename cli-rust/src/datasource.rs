@@ -0,0 +1,253 @@
+// Minimal S3-compatible object storage support so flight/airport/cancellation
+// sources can live in a bucket instead of (or alongside) the local data dir.
+//
+// This mirrors the basic-auth style already used for Elasticsearch in
+// `ElasticsearchConfig`: an access/secret key pair read from the same YAML
+// config is sent as HTTP basic credentials, rather than full AWS SigV4
+// signing. An access key without a secret key sends no credentials, and
+// there is no API-key header mode. That's enough for the
+// MinIO/Garage-style gateways this workshop targets.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::{Cursor, Read, Seek};
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjectStoreConfig {
+    pub endpoint: String,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub ssl_verify: bool,
+}
+
+impl ObjectStoreConfig {
+    /// Reads the optional top-level `s3:` section of `config/elasticsearch.yml`.
+    /// Returns `None` if the section is absent, which is fine as long as no
+    /// `s3://` source is requested.
+    pub fn from_yaml(data: &Value) -> Option<Self> {
+        let section = data.get("s3")?.as_object()?;
+
+        let normalize = |v: Option<&str>| -> Option<String> {
+            v.map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+
+        let endpoint = normalize(section.get("endpoint").and_then(|v| v.as_str()))?;
+        let access_key = normalize(section.get("access_key").and_then(|v| v.as_str()));
+        let secret_key = normalize(section.get("secret_key").and_then(|v| v.as_str()));
+        let ssl_verify = section
+            .get("ssl_verify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        Some(Self {
+            endpoint,
+            access_key,
+            secret_key,
+            ssl_verify,
+        })
+    }
+}
+
+/// A source location that `FlightLoader`, `AirportLookup`, and
+/// `CancellationLookup` can open a reader against, whether it lives on local
+/// disk or in an S3-compatible bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceLocation {
+    Local(String),
+    S3 { bucket: String, key: String },
+}
+
+impl SourceLocation {
+    pub fn parse(raw: &str) -> Self {
+        match raw.strip_prefix("s3://") {
+            Some(rest) => {
+                let mut parts = rest.splitn(2, '/');
+                let bucket = parts.next().unwrap_or("").to_string();
+                let key = parts.next().unwrap_or("").to_string();
+                SourceLocation::S3 { bucket, key }
+            }
+            None => SourceLocation::Local(raw.to_string()),
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, SourceLocation::S3 { .. })
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            SourceLocation::Local(path) => path.clone(),
+            SourceLocation::S3 { bucket, key } => format!("s3://{}/{}", bucket, key),
+        }
+    }
+}
+
+pub struct ObjectStore {
+    config: ObjectStoreConfig,
+    client: reqwest::blocking::Client,
+}
+
+impl ObjectStore {
+    pub fn new(config: ObjectStoreConfig) -> Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .danger_accept_invalid_certs(!config.ssl_verify)
+            .build()
+            .context("Failed to build object storage HTTP client")?;
+        Ok(Self { config, client })
+    }
+
+    fn authorize(
+        &self,
+        builder: reqwest::blocking::RequestBuilder,
+    ) -> reqwest::blocking::RequestBuilder {
+        match (&self.config.access_key, &self.config.secret_key) {
+            (Some(key), Some(secret)) => builder.basic_auth(key, Some(secret)),
+            _ => builder,
+        }
+    }
+
+    /// Streams the full object body. Callers that need gzip/zip decoding wrap
+    /// the returned reader the same way they already wrap a local `File`.
+    pub fn get(&self, bucket: &str, key: &str) -> Result<Box<dyn Read + Send>> {
+        let url = format!(
+            "{}/{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            bucket,
+            key.trim_start_matches('/')
+        );
+
+        let response = self
+            .authorize(self.client.get(&url))
+            .send()
+            .with_context(|| format!("Failed to GET s3://{}/{}", bucket, key))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Object fetch failed ({}) for s3://{}/{}",
+                response.status(),
+                bucket,
+                key
+            );
+        }
+
+        Ok(Box::new(response))
+    }
+
+    /// Lists keys under `prefix` using the S3 ListObjectsV2 XML API, enough to
+    /// implement `--glob`/`--all` against a bucket. Only the first page of
+    /// the listing is read; continuation tokens are not followed.
+    pub fn list(&self, bucket: &str, prefix: &str) -> Result<Vec<String>> {
+        let url = format!(
+            "{}/{}?list-type=2&prefix={}",
+            self.config.endpoint.trim_end_matches('/'),
+            bucket,
+            prefix
+        );
+
+        let response = self
+            .authorize(self.client.get(&url))
+            .send()
+            .with_context(|| format!("Failed to list s3://{}/{}", bucket, prefix))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Object listing failed ({}) for s3://{}/{}",
+                response.status(),
+                bucket,
+                prefix
+            );
+        }
+
+        let body = response.text()?;
+        Ok(parse_list_keys(&body))
+    }
+}
+
+/// Pulls `<Key>...</Key>` entries out of a ListObjectsV2 response without
+/// pulling in a full XML dependency.
+fn parse_list_keys(body: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("<Key>") {
+        let after_start = &rest[start + "<Key>".len()..];
+        if let Some(end) = after_start.find("</Key>") {
+            keys.push(after_start[..end].to_string());
+            rest = &after_start[end + "</Key>".len()..];
+        } else {
+            break;
+        }
+    }
+    keys
+}
+
+/// Opens a reader against either a local path or an S3 location, leaving
+/// gzip/zip detection to the caller exactly as it does for local files.
+pub fn open_source(
+    location: &SourceLocation,
+    store: Option<&ObjectStore>,
+) -> Result<Box<dyn Read + Send>> {
+    match location {
+        SourceLocation::Local(path) => {
+            let file = std::fs::File::open(path)
+                .with_context(|| format!("Failed to open {:?}", path))?;
+            Ok(Box::new(file))
+        }
+        SourceLocation::S3 { bucket, key } => {
+            let store = store.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "s3://{}/{} requested but no [s3] section found in the Elasticsearch config",
+                    bucket,
+                    key
+                )
+            })?;
+            store.get(bucket, key)
+        }
+    }
+}
+
+/// A seekable source, backed by a local file or by the fully-buffered body of
+/// a remote object. Zip archives need random access, which an S3 GET response
+/// doesn't offer directly, so remote zip entries are read fully into memory.
+pub enum SeekableSource {
+    Local(std::fs::File),
+    Buffered(Cursor<Vec<u8>>),
+}
+
+impl Read for SeekableSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            SeekableSource::Local(f) => f.read(buf),
+            SeekableSource::Buffered(c) => c.read(buf),
+        }
+    }
+}
+
+impl Seek for SeekableSource {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        match self {
+            SeekableSource::Local(f) => f.seek(pos),
+            SeekableSource::Buffered(c) => c.seek(pos),
+        }
+    }
+}
+
+/// Like [`open_source`], but seekable (see [`SeekableSource`]).
+pub fn open_seekable(
+    location: &SourceLocation,
+    store: Option<&ObjectStore>,
+) -> Result<SeekableSource> {
+    match location {
+        SourceLocation::Local(path) => {
+            let file = std::fs::File::open(path)
+                .with_context(|| format!("Failed to open {:?}", path))?;
+            Ok(SeekableSource::Local(file))
+        }
+        SourceLocation::S3 { .. } => {
+            let mut reader = open_source(location, store)?;
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf)?;
+            Ok(SeekableSource::Buffered(Cursor::new(buf)))
+        }
+    }
+}
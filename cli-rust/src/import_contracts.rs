@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use base64::{Engine as _, engine::general_purpose};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use elasticsearch::{
     auth::Credentials,
     cert::CertificateValidation,
@@ -10,16 +10,26 @@ use elasticsearch::{
         transport::{SingleNodeConnectionPool, TransportBuilder},
         Url,
     },
-    indices::{IndicesCreateParts, IndicesDeleteParts, IndicesExistsParts},
+    indices::{
+        IndicesCreateParts, IndicesDeleteParts, IndicesExistsParts, IndicesGetAliasParts,
+        IndicesUpdateAliasesParts,
+    },
     ingest::IngestPutPipelineParts,
     params::Refresh,
-    Elasticsearch,
+    BulkOperation, BulkParts, Elasticsearch, ExistsParts,
 };
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::task::JoinSet;
+
+mod pdf_source;
+use pdf_source::{resolve_pdf_source, PdfSource, S3SourceConfig, SourceRef};
 
 // Simple logging macros
 macro_rules! info {
@@ -43,6 +53,16 @@ macro_rules! error {
 const ES_INDEX: &str = "contracts";
 const PIPELINE_NAME: &str = "pdf_pipeline";
 const DEFAULT_INFERENCE_ENDPOINT: &str = ".elser-2-elastic";
+// Base64 PDF payloads are large, so batches are capped by cumulative body
+// size rather than a fixed document count.
+const BULK_MAX_BYTES: usize = 10 * 1024 * 1024;
+
+/// Where `ingest_pdfs`/`ingest_ndjson` get the documents they bulk-index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    Pdf,
+    Ndjson,
+}
 
 #[derive(Parser)]
 #[command(name = "import_contracts")]
@@ -54,8 +74,17 @@ struct Args {
     #[arg(short = 'm', long, default_value = "config/mappings-contracts.json")]
     mapping: PathBuf,
 
+    /// Local file/directory, `s3://bucket/prefix`, or an `http(s)://` URL to
+    /// a manifest listing one PDF URL per line (`--input-format pdf`), or a
+    /// local `.jsonl` file of pre-built documents (`--input-format ndjson`).
     #[arg(long)]
-    pdf_path: Option<PathBuf>,
+    pdf_path: Option<String>,
+
+    /// `pdf` extracts PDFs as today; `ndjson` streams a newline-delimited
+    /// JSON file of already-built documents straight into the same bulk
+    /// machinery, one line at a time, without the PDF ingest pipeline.
+    #[arg(long, value_enum, default_value_t = InputFormat::Pdf)]
+    input_format: InputFormat,
 
     #[arg(long)]
     setup_only: bool,
@@ -63,9 +92,60 @@ struct Args {
     #[arg(long)]
     ingest_only: bool,
 
+    /// Number of PDFs to read and base64-encode concurrently before bulk-indexing them
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Skip PDFs whose content-hash _id already exists in the write index,
+    /// so re-running over a watched directory doesn't reprocess unchanged
+    /// files. Only meaningful with --ingest-only: a plain run always writes
+    /// into a freshly created, empty physical index, where this check can
+    /// never find anything to skip.
+    #[arg(long, requires = "ingest_only")]
+    skip_existing: bool,
+
+    /// Don't delete the previous generation's physical index after an alias
+    /// swap; leave it un-aliased on disk for manual rollback
+    #[arg(long)]
+    keep_old: bool,
+
+    /// Number of `_bulk` requests allowed in flight at once
+    #[arg(long, default_value_t = 2)]
+    bulk_concurrency: usize,
+
+    /// Retries for a `_bulk` request that fails with a retryable status
+    /// (429, 503, 504, or a dropped connection), with exponential backoff
+    #[arg(long, default_value_t = 3)]
+    max_retries: usize,
+
     #[arg(long)]
     inference_endpoint: Option<String>,
 
+    /// NDJSON file to append failed documents (source payload + ES error)
+    /// to, so a later `--retry-dead-letter` run can re-ingest just those
+    #[arg(long)]
+    dead_letter: Option<PathBuf>,
+
+    /// Re-ingest only the documents recorded in this dead-letter file,
+    /// instead of running the normal PDF ingestion pipeline
+    #[arg(long)]
+    retry_dead_letter: Option<PathBuf>,
+
+    /// JSON sidecar recording which files (by content hash) have already
+    /// been indexed successfully, so a `--resume` run can skip them
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Skip any file whose content hash already appears in `--checkpoint`
+    /// as indexed, instead of re-processing (and re-embedding) everything.
+    /// Only valid with --ingest-only: a plain run always provisions a fresh,
+    /// empty physical index, so checkpointed documents (which only exist in
+    /// the *old* one) would be dropped for good once the alias swap deletes
+    /// it. --ingest-only appends to the index the alias already points at,
+    /// where those documents are still live.
+    #[arg(long, requires = "ingest_only")]
+    resume: bool,
+
     #[arg(long)]
     status: bool,
 }
@@ -78,6 +158,11 @@ struct ElasticsearchConfig {
     password: Option<String>,
     api_key: Option<String>,
     ssl_verify: bool,
+    /// Gzip-compress request bodies. Worth turning off only against a
+    /// cluster old enough not to support it; PDF bodies are large enough
+    /// that compression is otherwise a clear bandwidth win. zstd is not
+    /// used, and response compression is not governed by this flag.
+    compression: bool,
 }
 
 impl ElasticsearchConfig {
@@ -113,6 +198,11 @@ impl ElasticsearchConfig {
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
 
+        let compression = data
+            .get("compression")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
         Ok(Self {
             endpoint,
             headers,
@@ -120,10 +210,133 @@ impl ElasticsearchConfig {
             password,
             api_key,
             ssl_verify,
+            compression,
         })
     }
 }
 
+/// Stable, machine-readable error taxonomy for setup/ingestion failures, so
+/// callers (and CI scripts parsing the JSON summary `ingest_pdfs` prints)
+/// can distinguish e.g. an auth failure from a missing inference endpoint
+/// instead of matching on ad-hoc message strings.
+#[derive(Debug, Error)]
+enum ImportError {
+    #[error("failed to connect to Elasticsearch: {0}")]
+    ConnectionFailed(String),
+
+    #[error("authentication rejected (HTTP {status}): {message}")]
+    AuthRejected { status: u16, message: String },
+
+    #[error("ELSER inference endpoint '{0}' not found")]
+    InferenceEndpointMissing(String),
+
+    #[error("index operation conflicted (HTTP {status}): {message}")]
+    IndexConflict { status: u16, message: String },
+
+    #[error("pipeline error (HTTP {status}): {message}")]
+    PipelineError { status: u16, message: String },
+
+    #[error("failed to read/encode PDF attachment: {message}")]
+    AttachmentExtractionFailed { message: String },
+
+    #[error("document rejected (HTTP {status}): {message}")]
+    DocumentRejected { status: u16, message: String },
+}
+
+impl ImportError {
+    /// Stable code for the JSON failure summary, independent of the
+    /// human-readable `Display` text.
+    fn code(&self) -> &'static str {
+        match self {
+            ImportError::ConnectionFailed(_) => "connection_failed",
+            ImportError::AuthRejected { .. } => "auth_rejected",
+            ImportError::InferenceEndpointMissing(_) => "inference_endpoint_missing",
+            ImportError::IndexConflict { .. } => "index_conflict",
+            ImportError::PipelineError { .. } => "pipeline_error",
+            ImportError::AttachmentExtractionFailed { .. } => "attachment_extraction_failed",
+            ImportError::DocumentRejected { .. } => "document_rejected",
+        }
+    }
+}
+
+/// One `_bulk` item Elasticsearch rejected: its label/id, the source
+/// document it was built from (so it can be written to a dead-letter file
+/// and re-ingested later), and the ES error's `type`/`reason` rather than
+/// just the error's stringified JSON.
+#[derive(Debug, Clone)]
+struct FailedDocument {
+    label: String,
+    id: String,
+    document: Value,
+    error_type: String,
+    reason: String,
+}
+
+/// Result of one `bulk_index_documents` call: how many documents were
+/// indexed, and the details of each one that wasn't, so a batch failure
+/// doesn't hide which files need a retry.
+#[derive(Default)]
+struct BulkIndexOutcome {
+    indexed: usize,
+    failed: Vec<FailedDocument>,
+}
+
+/// A `--checkpoint` sidecar recording, per source label (filename/path),
+/// the content hash it was last indexed under. `--resume` consults this to
+/// skip files whose hash is unchanged, so a failure partway through a large
+/// `data` directory doesn't force re-reading and re-embedding (expensive,
+/// under ELSER) every file from scratch.
+#[derive(Debug, Default, Clone)]
+struct CheckpointManifest {
+    indexed: HashMap<String, String>,
+}
+
+impl CheckpointManifest {
+    /// Loads an existing manifest, or an empty one if `path` doesn't exist yet.
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint manifest {:?}", path))?;
+        let value: Value = serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse checkpoint manifest {:?}", path))?;
+        let indexed = value
+            .get("indexed")
+            .and_then(|v| v.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(label, hash)| {
+                        hash.as_str().map(|h| (label.clone(), h.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self { indexed })
+    }
+
+    /// Whether `label` was last indexed under this exact `hash`.
+    fn is_current(&self, label: &str, hash: &str) -> bool {
+        self.indexed.get(label).map(|h| h == hash).unwrap_or(false)
+    }
+
+    fn record(&mut self, label: String, hash: String) {
+        self.indexed.insert(label, hash);
+    }
+
+    fn len(&self) -> usize {
+        self.indexed.len()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let value = json!({ "indexed": self.indexed });
+        fs::write(path, serde_json::to_string_pretty(&value)?)
+            .with_context(|| format!("Failed to write checkpoint manifest {:?}", path))?;
+        Ok(())
+    }
+}
+
 struct ElasticsearchClient {
     client: Elasticsearch,
     endpoint: String,
@@ -140,6 +353,14 @@ impl ElasticsearchClient {
             builder = builder.cert_validation(CertificateValidation::None);
         }
 
+        if config.compression {
+            // Gzip-compress request bodies (the base64 PDF payloads in
+            // particular). Response decompression is handled transparently
+            // by the transport/reqwest layer and isn't controlled by this
+            // flag.
+            builder = builder.request_body_compression(true);
+        }
+
         if !config.headers.is_empty() {
             let mut header_map = HeaderMap::new();
             for (k, v) in config.headers.iter() {
@@ -185,7 +406,8 @@ impl ElasticsearchClient {
             .create(IndicesCreateParts::Index(name))
             .body(mapping.clone())
             .send()
-            .await?;
+            .await
+            .map_err(|e| ImportError::ConnectionFailed(e.to_string()))?;
 
         let status = response.status_code();
         if status.is_success() {
@@ -193,8 +415,13 @@ impl ElasticsearchClient {
         } else if status.as_u16() == 409 {
             warn!("Index '{}' already exists (conflict)", name);
         } else {
+            let status = status.as_u16();
             let text = response.text().await?;
-            anyhow::bail!("Index creation failed ({}): {}", status, text);
+            let err = match status {
+                401 | 403 => ImportError::AuthRejected { status, message: text },
+                _ => ImportError::IndexConflict { status, message: text },
+            };
+            return Err(err.into());
         }
         Ok(())
     }
@@ -218,6 +445,53 @@ impl ElasticsearchClient {
         }
     }
 
+    /// Resolves an alias to every physical index currently backing it
+    /// (normally one, but can accumulate more if a prior run's cleanup
+    /// failed), or an empty list if `alias` doesn't exist yet (e.g. first
+    /// run, or it's still a plain index from before alias-swap was
+    /// introduced).
+    async fn get_alias_targets(&self, alias: &str) -> Result<Vec<String>> {
+        let response = self
+            .client
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias]))
+            .send()
+            .await?;
+
+        if response.status_code().as_u16() == 404 {
+            return Ok(Vec::new());
+        }
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Failed to resolve alias '{}': {}", alias, text);
+        }
+
+        let parsed: Value = response.json().await?;
+        Ok(parsed
+            .as_object()
+            .map(|obj| obj.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Applies a batch of `{"add": ...}`/`{"remove": ...}` alias actions in
+    /// one atomic `_aliases` call, so a reindex swap never leaves a window
+    /// where the alias points at nothing or at both indices.
+    async fn update_aliases(&self, actions: Vec<Value>) -> Result<()> {
+        let response = self
+            .client
+            .indices()
+            .update_aliases(IndicesUpdateAliasesParts::None)
+            .body(json!({"actions": actions}))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Alias update failed: {}", text);
+        }
+        Ok(())
+    }
+
     async fn cluster_health(&self) -> Result<Value> {
         let response = self
             .client
@@ -242,71 +516,93 @@ impl ElasticsearchClient {
             .put_pipeline(IngestPutPipelineParts::Id(name))
             .body(pipeline_config.clone())
             .send()
-            .await?;
+            .await
+            .map_err(|e| ImportError::ConnectionFailed(e.to_string()))?;
 
         if !response.status_code().is_success() {
+            let status = response.status_code().as_u16();
             let text = response.text().await?;
-            anyhow::bail!("Pipeline creation failed: {}", text);
+            let err = match status {
+                401 | 403 => ImportError::AuthRejected { status, message: text },
+                _ => ImportError::PipelineError { status, message: text },
+            };
+            return Err(err.into());
         }
 
         info!("Pipeline '{}' created/updated", name);
         Ok(())
     }
 
-    async fn index_document(
+    /// Indexes `documents` (each a label used only for error reporting, e.g.
+    /// a filename; a content-hash `_id`; and the document body) in a single
+    /// `_bulk` request, refreshing once at the end instead of per document.
+    /// Per-item failures are collected rather than aborting the batch.
+    async fn bulk_index_documents(
         &self,
         index_name: &str,
-        document: &Value,
+        documents: &[(String, String, Value)],
         pipeline: Option<&str>,
-    ) -> Result<()> {
-        use elasticsearch::IndexParts;
-        
+    ) -> Result<BulkIndexOutcome> {
+        let operations = documents
+            .iter()
+            .map(|(_, id, doc)| BulkOperation::index(doc.clone()).id(id).into())
+            .collect::<Vec<BulkOperation<_>>>();
+
         let mut request = self
             .client
-            .index(IndexParts::Index(index_name))
-            .body(document.clone())
-            .refresh(Refresh::WaitFor); // Wait for refresh to ensure document is searchable
+            .bulk(BulkParts::Index(index_name))
+            .body(operations)
+            .refresh(Refresh::WaitFor);
 
         if let Some(pipeline) = pipeline {
             request = request.pipeline(pipeline);
         }
 
-        let response = request.send().await;
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ImportError::ConnectionFailed(e.to_string()))?;
+        let status = response.status_code();
+        if !status.is_success() {
+            let status = status.as_u16();
+            let text = response.text().await?;
+            let err = match status {
+                401 | 403 => ImportError::AuthRejected { status, message: text },
+                _ => ImportError::DocumentRejected { status, message: text },
+            };
+            return Err(err.into());
+        }
 
-        match response {
-            Ok(resp) => {
-                let status = resp.status_code();
-                
-                if !status.is_success() {
-                    // For error status, try to get error message
-                    match resp.text().await {
-                        Ok(text) => anyhow::bail!("Document indexing failed ({}): {}", status, text),
-                        Err(_) => anyhow::bail!("Document indexing failed with status {}", status),
-                    }
-                }
-                
-                // For success status, try to parse JSON to check for errors in response body
-                // (some pipeline errors might still return 200 OK)
-                match resp.json::<Value>().await {
-                    Ok(json) => {
-                        if let Some(error) = json.get("error") {
-                            warn!("Elasticsearch returned error in response: {}", error);
-                            anyhow::bail!("Document indexing failed: {}", error);
-                        }
-                        Ok(())
-                    }
-                    Err(_) => {
-                        // If we can't parse JSON but status was OK, assume success
-                        Ok(())
-                    }
+        let result: Value = response.json().await?;
+        let empty: Vec<Value> = Vec::new();
+        let items = result.get("items").and_then(|v| v.as_array()).unwrap_or(&empty);
+
+        let mut outcome = BulkIndexOutcome::default();
+        for (item, (label, id, document)) in items.iter().zip(documents.iter()) {
+            match item.get("index").and_then(|action| action.get("error")) {
+                None => outcome.indexed += 1,
+                Some(error) => {
+                    let error_type = error
+                        .get("type")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    let reason = error
+                        .get("reason")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| error.to_string());
+                    outcome.failed.push(FailedDocument {
+                        label: label.clone(),
+                        id: id.clone(),
+                        document: document.clone(),
+                        error_type,
+                        reason,
+                    });
                 }
             }
-            Err(e) => {
-                // Log the full error for debugging
-                error!("Elasticsearch error during indexing: {:?}", e);
-                Err(e.into())
-            }
         }
+        Ok(outcome)
     }
 
     async fn get_inference_endpoints(&self) -> Result<Value> {
@@ -375,13 +671,294 @@ impl ElasticsearchClient {
             .unwrap_or(0);
         Ok(count)
     }
+
+    /// Checks whether a document with the given `_id` already exists in
+    /// `index_name`, so `--skip-existing` can avoid reprocessing unchanged PDFs.
+    async fn document_exists(&self, index_name: &str, id: &str) -> Result<bool> {
+        let response = self
+            .client
+            .exists(ExistsParts::IndexId(index_name, id))
+            .send()
+            .await?;
+        Ok(response.status_code().is_success())
+    }
+}
+
+fn extract_airline_name(filename: &str) -> String {
+    let filename_lower = filename.to_lowercase();
+
+    if filename_lower.contains("american") {
+        "American Airlines".to_string()
+    } else if filename_lower.contains("southwest") {
+        "Southwest".to_string()
+    } else if filename_lower.contains("united") {
+        "United".to_string()
+    } else if filename_lower.contains("delta") || filename_lower.contains("dl-") {
+        "Delta".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+/// Records every `(label, id)` from a finished flush into `checkpoint`,
+/// except the ones that just failed -- so a later `--resume` run only
+/// skips files that actually made it into the index.
+fn record_checkpoint_successes(
+    checkpoint: &mut CheckpointManifest,
+    batch_ids: Vec<(String, String)>,
+    newly_failed: &[FailedDocument],
+) {
+    let failed_ids: std::collections::HashSet<&str> =
+        newly_failed.iter().map(|f| f.id.as_str()).collect();
+    for (label, id) in batch_ids {
+        if !failed_ids.contains(id.as_str()) {
+            checkpoint.record(label, id);
+        }
+    }
+}
+
+/// SHA-256 over the raw PDF bytes, hex-encoded, used as the document `_id`
+/// so re-importing an unchanged file overwrites the same document instead of
+/// creating a duplicate, and a changed file deterministically gets a new one.
+fn content_id(pdf_data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(pdf_data);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Fetches one PDF's bytes through `source` and base64-encodes them into the
+/// document shape `bulk_index_documents` expects, alongside the content-hash
+/// `_id` it should be indexed under. A free function (no `&self`) so
+/// `ingest_pdfs` can run it inside a `spawn_blocking` task without holding a
+/// borrow of the loader across the `.await` boundary.
+fn prepare_pdf_document(source: &dyn PdfSource, entry: &SourceRef) -> Result<(String, String, Value)> {
+    let filename = entry.label();
+    let airline = extract_airline_name(&filename);
+
+    let pdf_data = source.read(entry)?;
+    let id = content_id(&pdf_data);
+    let encoded_pdf = general_purpose::STANDARD.encode(&pdf_data);
+
+    let document = json!({
+        "data": encoded_pdf,
+        "filename": filename,
+        "airline": airline
+    });
+
+    Ok((filename, id, document))
+}
+
+/// Whether a failed `_bulk` request is worth retrying: a dropped connection,
+/// or a cluster-side status that usually clears up on its own (rate
+/// limiting, or a node temporarily unavailable/timing out). Anything else --
+/// a rejected auth header, a malformed document -- will fail the same way
+/// every time, so retrying it just delays the inevitable.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<ImportError>() {
+        Some(ImportError::ConnectionFailed(_)) => true,
+        Some(ImportError::DocumentRejected { status, .. }) => matches!(status, 429 | 503 | 504),
+        _ => false,
+    }
+}
+
+/// Exponential backoff with a little jitter, so a batch of in-flight bulk
+/// requests that all hit a 429 at once don't all retry in lockstep.
+fn backoff_delay(attempt: u32) -> std::time::Duration {
+    let base_ms = 250u64.saturating_mul(1u64 << attempt.min(6));
+    let jitter_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_millis() as u64 % 100)
+        .unwrap_or(0);
+    std::time::Duration::from_millis(base_ms.min(10_000) + jitter_ms)
+}
+
+/// Sends one `_bulk` request, retrying with [`backoff_delay`] on
+/// [`is_retryable`] failures up to `max_retries` times. Returns the outcome
+/// alongside how many retries it took, so a caller can tell a clean
+/// first-try success from one that only went through after transient
+/// errors.
+async fn bulk_index_with_retry(
+    client: &ElasticsearchClient,
+    index_name: &str,
+    documents: &[(String, String, Value)],
+    pipeline: Option<&str>,
+    max_retries: usize,
+) -> (Result<BulkIndexOutcome>, usize) {
+    let mut attempt = 0usize;
+    loop {
+        match client
+            .bulk_index_documents(index_name, documents, pipeline)
+            .await
+        {
+            Ok(outcome) => return (Ok(outcome), attempt),
+            Err(e) if attempt < max_retries && is_retryable(&e) => {
+                let delay = backoff_delay(attempt as u32);
+                warn!(
+                    "Bulk request failed ({}), retrying in {:?} (attempt {}/{})",
+                    e,
+                    delay,
+                    attempt + 1,
+                    max_retries
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return (Err(e), attempt),
+        }
+    }
+}
+
+/// Which backend turns `semantic_content` into vectors: Elasticsearch's
+/// built-in ELSER sparse endpoint (the original/default behavior), or an
+/// external dense-embedding HTTP service -- OpenAI, or a local Ollama
+/// server -- that the ingest pipeline calls out to instead. Lets the
+/// workshop run fully offline against Ollama, or against OpenAI, without
+/// anyone having to deploy ELSER.
+#[derive(Debug, Clone)]
+enum EmbedderBackend {
+    Elser,
+    Http(HttpEmbedderConfig),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HttpEmbedderKind {
+    Openai,
+    Ollama,
+}
+
+impl HttpEmbedderKind {
+    fn label(&self) -> &'static str {
+        match self {
+            HttpEmbedderKind::Openai => "openai",
+            HttpEmbedderKind::Ollama => "ollama",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HttpEmbedderConfig {
+    kind: HttpEmbedderKind,
+    model: String,
+    base_url: String,
+    api_key_env: Option<String>,
+    dims: usize,
+}
+
+impl EmbedderBackend {
+    /// Reads the optional top-level `embedder:` section of
+    /// `config/elasticsearch.yml`. Absent entirely, or `backend: elser`,
+    /// keeps the original ELSER-only behavior.
+    fn from_yaml(data: &Value) -> Result<Self> {
+        let Some(section) = data.get("embedder").and_then(|v| v.as_object()) else {
+            return Ok(EmbedderBackend::Elser);
+        };
+
+        let backend = section
+            .get("backend")
+            .and_then(|v| v.as_str())
+            .unwrap_or("elser");
+
+        let kind = match backend {
+            "elser" => return Ok(EmbedderBackend::Elser),
+            "openai" => HttpEmbedderKind::Openai,
+            "ollama" => HttpEmbedderKind::Ollama,
+            other => anyhow::bail!(
+                "Unknown embedder.backend '{}' (expected elser, openai, or ollama)",
+                other
+            ),
+        };
+
+        let model = section
+            .get("model")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                anyhow::anyhow!("embedder.model is required for the {} backend", backend)
+            })?
+            .to_string();
+
+        let base_url = section
+            .get("base_url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                anyhow::anyhow!("embedder.base_url is required for the {} backend", backend)
+            })?
+            .trim_end_matches('/')
+            .to_string();
+
+        let api_key_env = section
+            .get("api_key_env")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let dims = section
+            .get("dims")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1536) as usize;
+
+        Ok(EmbedderBackend::Http(HttpEmbedderConfig {
+            kind,
+            model,
+            base_url,
+            api_key_env,
+            dims,
+        }))
+    }
 }
 
 struct ContractLoader {
-    client: ElasticsearchClient,
+    client: Arc<ElasticsearchClient>,
     mapping: Value,
     inference_endpoint: String,
+    embedder: EmbedderBackend,
     indexed_count: usize,
+    /// Set once `create_index` provisions a fresh `contracts-<timestamp>`
+    /// index this run; `finalize_index_swap` repoints the `ES_INDEX` alias
+    /// to it and clears this back to `None`. Stays `None` for a plain
+    /// `--ingest-only` run, which instead appends to whatever the alias
+    /// already points at.
+    physical_index: Option<String>,
+    /// Number of PDFs to read and base64-encode concurrently in `ingest_pdfs`,
+    /// via a `JoinSet` of `spawn_blocking` tasks (mirrors the `--concurrency`
+    /// pipeline in `main.rs`).
+    concurrency: usize,
+    /// Number of `_bulk` requests `ingest_pdfs` allows in flight at once,
+    /// enforced with a `tokio::sync::Semaphore` rather than the
+    /// `JoinSet`-length check used for PDF preparation, since bulk requests
+    /// are dispatched eagerly as soon as a batch fills rather than pulled
+    /// from a fixed work queue.
+    bulk_concurrency: usize,
+    /// Retries for a `_bulk` request that comes back with a retryable
+    /// status (429/503/504, or a dropped connection), with exponential
+    /// backoff between attempts.
+    max_retries: usize,
+    /// When set, `ingest_pdfs` skips any PDF whose content-hash `_id` is
+    /// already present in the write index instead of re-indexing it.
+    skip_existing: bool,
+    /// When set, `finalize_index_swap` leaves stale physical indices in
+    /// place (un-aliased) instead of deleting them, so a bad generation can
+    /// be rolled back to manually by re-pointing the alias.
+    keep_old: bool,
+    /// Documents indexed only after one or more retries, vs. documents that
+    /// were never indexed: per-item rejections in a bulk response (not
+    /// retried, whatever their status, a per-item 429 included), plus every
+    /// document of a request that failed with a non-retryable error or ran
+    /// out of retries. `verify_ingestion` reports both.
+    retried_success_count: usize,
+    permanently_failed_count: usize,
+    /// When set, every permanently-failed document from a bulk response is
+    /// appended here as NDJSON (source payload + ES error), for a later
+    /// `--retry-dead-letter` run to pick back up.
+    dead_letter_path: Option<PathBuf>,
+    /// When set, `ingest_pdfs` loads/saves a [`CheckpointManifest`] here,
+    /// recording each file indexed this run.
+    checkpoint_path: Option<PathBuf>,
+    /// When set, files whose hash already appears in the checkpoint
+    /// manifest as indexed are skipped instead of re-processed.
+    resume: bool,
 }
 
 impl ContractLoader {
@@ -389,13 +966,34 @@ impl ContractLoader {
         client: ElasticsearchClient,
         mapping: Value,
         inference_endpoint: Option<String>,
+        embedder: EmbedderBackend,
+        concurrency: usize,
+        bulk_concurrency: usize,
+        max_retries: usize,
+        skip_existing: bool,
+        keep_old: bool,
+        dead_letter_path: Option<PathBuf>,
+        checkpoint_path: Option<PathBuf>,
+        resume: bool,
     ) -> Self {
         Self {
-            client,
+            client: Arc::new(client),
             mapping,
             inference_endpoint: inference_endpoint
                 .unwrap_or_else(|| DEFAULT_INFERENCE_ENDPOINT.to_string()),
+            embedder,
             indexed_count: 0,
+            physical_index: None,
+            skip_existing,
+            keep_old,
+            dead_letter_path,
+            checkpoint_path,
+            resume,
+            concurrency: concurrency.max(1),
+            bulk_concurrency: bulk_concurrency.max(1),
+            max_retries,
+            retried_success_count: 0,
+            permanently_failed_count: 0,
         }
     }
 
@@ -418,7 +1016,69 @@ impl ContractLoader {
         }
     }
 
+    /// Validates whichever embedder `embedder:` selected: the ES-hosted
+    /// ELSER endpoint as before, or a reachability ping against the
+    /// configured OpenAI/Ollama `base_url` for the HTTP backends.
     async fn check_inference_endpoint(&mut self) -> Result<bool> {
+        match self.embedder.clone() {
+            EmbedderBackend::Elser => self.check_elser_endpoint().await,
+            EmbedderBackend::Http(cfg) => self.check_http_embedder(&cfg).await,
+        }
+    }
+
+    async fn check_http_embedder(&self, cfg: &HttpEmbedderConfig) -> Result<bool> {
+        if let Some(env_var) = &cfg.api_key_env {
+            if std::env::var(env_var).is_err() {
+                warn!(
+                    "Environment variable '{}' is not set; the {} embedder may reject requests",
+                    env_var,
+                    cfg.kind.label()
+                );
+            }
+        }
+
+        let url = match cfg.kind {
+            HttpEmbedderKind::Openai => format!("{}/models", cfg.base_url),
+            HttpEmbedderKind::Ollama => format!("{}/api/tags", cfg.base_url),
+        };
+
+        let mut request = reqwest::Client::new().get(&url);
+        if cfg.kind == HttpEmbedderKind::Openai {
+            if let Some(key) = cfg
+                .api_key_env
+                .as_ref()
+                .and_then(|env_var| std::env::var(env_var).ok())
+            {
+                request = request.bearer_auth(key);
+            }
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                info!(
+                    "Reached {} embedder at {}",
+                    cfg.kind.label(),
+                    cfg.base_url
+                );
+                Ok(true)
+            }
+            Ok(response) => {
+                error!(
+                    "{} embedder at {} returned HTTP {}",
+                    cfg.kind.label(),
+                    cfg.base_url,
+                    response.status()
+                );
+                Ok(false)
+            }
+            Err(e) => {
+                error!("Failed to reach {} embedder at {}: {}", cfg.kind.label(), cfg.base_url, e);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn check_elser_endpoint(&mut self) -> Result<bool> {
         match self.client.get_inference_endpoints().await {
             Ok(response) => {
                 let endpoints = response
@@ -480,7 +1140,8 @@ impl ContractLoader {
                     return Ok(true);
                 }
 
-                error!("Inference endpoint '{}' not found", self.inference_endpoint);
+                let err = ImportError::InferenceEndpointMissing(self.inference_endpoint.clone());
+                error!("[{}] {}", err.code(), err);
                 info!("Available endpoints:");
                 for ep in endpoints {
                     if let Some(id) = ep.get("inference_id").and_then(|v| v.as_str()) {
@@ -498,36 +1159,61 @@ impl ContractLoader {
     }
 
     async fn create_pipeline(&self) -> Result<bool> {
-        let pipeline_config = json!({
-            "description": "Extract text from PDF - semantic_text field handles chunking and embeddings",
-            "processors": [
-                {
-                    "attachment": {
-                        "field": "data",
-                        "target_field": "attachment",
-                        "remove_binary": true
-                    }
-                },
-                {
-                    "set": {
+        let mut processors = vec![
+            json!({
+                "attachment": {
+                    "field": "data",
+                    "target_field": "attachment",
+                    "remove_binary": true
+                }
+            }),
+            json!({
+                "set": {
+                    "field": "semantic_content",
+                    "copy_from": "attachment.content",
+                    "ignore_empty_value": true
+                }
+            }),
+            json!({
+                "remove": {
+                    "field": "data",
+                    "ignore_missing": true
+                }
+            }),
+        ];
+
+        // ELSER's `semantic_text` mapping field does its own chunking and
+        // sparse_embedding inference at index time, so no extra processor is
+        // needed here. The HTTP embedders have no such field type, so the
+        // pipeline itself has to call out and write a plain dense vector.
+        let description = match &self.embedder {
+            EmbedderBackend::Elser => {
+                "Extract text from PDF - semantic_text field handles chunking and embeddings"
+            }
+            EmbedderBackend::Http(cfg) => {
+                processors.push(json!({
+                    "text_embedding": {
                         "field": "semantic_content",
-                        "copy_from": "attachment.content",
-                        "ignore_empty_value": true
-                    }
-                },
-                {
-                    "remove": {
-                        "field": "data",
+                        "target_field": "content_embedding",
+                        "model": cfg.model,
+                        "base_url": cfg.base_url,
                         "ignore_missing": true
                     }
-                },
-                {
-                    "set": {
-                        "field": "upload_date",
-                        "value": "{{ _ingest.timestamp }}"
-                    }
-                }
-            ]
+                }));
+                "Extract text from PDF and embed it via an external text_embedding processor"
+            }
+        };
+
+        processors.push(json!({
+            "set": {
+                "field": "upload_date",
+                "value": "{{ _ingest.timestamp }}"
+            }
+        }));
+
+        let pipeline_config = json!({
+            "description": description,
+            "processors": processors
         });
 
         match self.client.create_pipeline(PIPELINE_NAME, &pipeline_config).await {
@@ -539,40 +1225,63 @@ impl ContractLoader {
         }
     }
 
+    /// Provisions a fresh `contracts-<timestamp>` index rather than wiping
+    /// the live `ES_INDEX` alias's current target, so existing search
+    /// traffic keeps hitting real data until `finalize_index_swap` cuts
+    /// over. Stores the new physical name in `self.physical_index` for
+    /// `resolve_write_index`/`finalize_index_swap` to pick up.
     async fn create_index(&mut self) -> Result<bool> {
-        // Delete index if it exists before creating a new one
-        if self.client.index_exists(ES_INDEX).await? {
-            info!("Deleting existing index '{}' before import", ES_INDEX);
-            match self.client.delete_index(ES_INDEX).await {
-                Ok(true) => info!("Index '{}' deleted", ES_INDEX),
-                Ok(false) => warn!("Failed to delete index '{}'", ES_INDEX),
-                Err(e) => warn!("Error deleting index '{}': {}", ES_INDEX, e),
-            }
-        }
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let physical = format!("{}-{}", ES_INDEX, timestamp);
 
-        // Update mapping with detected inference endpoint
+        // Update mapping for whichever embedder is configured.
         let mut mapping_with_inference = self.mapping.clone();
-        if let Some(mappings) = mapping_with_inference.get_mut("mappings") {
-            if let Some(properties) = mappings.get_mut("properties") {
-                if let Some(semantic_content) = properties.get_mut("semantic_content") {
-                    if let Some(obj) = semantic_content.as_object_mut() {
-                        obj.insert(
-                            "inference_id".to_string(),
-                            json!(self.inference_endpoint),
-                        );
+        match &self.embedder {
+            EmbedderBackend::Elser => {
+                if let Some(mappings) = mapping_with_inference.get_mut("mappings") {
+                    if let Some(properties) = mappings.get_mut("properties") {
+                        if let Some(semantic_content) = properties.get_mut("semantic_content") {
+                            if let Some(obj) = semantic_content.as_object_mut() {
+                                obj.insert(
+                                    "inference_id".to_string(),
+                                    json!(self.inference_endpoint),
+                                );
+                            }
+                        }
                     }
                 }
             }
+            EmbedderBackend::Http(cfg) => {
+                // The HTTP embedders write a plain vector via the
+                // `text_embedding` ingest processor rather than
+                // Elasticsearch's own semantic_text inference, so
+                // `content_embedding` needs its own dense_vector mapping
+                // sized to the configured model.
+                if let Some(properties) = mapping_with_inference
+                    .get_mut("mappings")
+                    .and_then(|m| m.get_mut("properties"))
+                    .and_then(|p| p.as_object_mut())
+                {
+                    properties.insert(
+                        "content_embedding".to_string(),
+                        json!({ "type": "dense_vector", "dims": cfg.dims }),
+                    );
+                }
+            }
         }
 
-        info!("Creating index: {}", ES_INDEX);
+        info!("Creating index: {}", physical);
         match self
             .client
-            .create_index(ES_INDEX, &mapping_with_inference)
+            .create_index(&physical, &mapping_with_inference)
             .await
         {
             Ok(_) => {
-                info!("Successfully created index: {}", ES_INDEX);
+                info!("Successfully created index: {}", physical);
+                self.physical_index = Some(physical);
                 Ok(true)
             }
             Err(e) => {
@@ -582,136 +1291,634 @@ impl ContractLoader {
         }
     }
 
-    fn extract_airline_name(&self, filename: &str) -> String {
-        let filename_lower = filename.to_lowercase();
+    /// Picks which physical index PDF ingestion should write into: the one
+    /// `create_index` just provisioned this run, or (for a standalone
+    /// `--ingest-only` run) whatever the `ES_INDEX` alias already points to.
+    /// Falls back to treating `ES_INDEX` as a plain index if it predates
+    /// alias-swap, and errors out if neither exists yet.
+    async fn resolve_write_index(&self) -> Result<String> {
+        if let Some(physical) = &self.physical_index {
+            return Ok(physical.clone());
+        }
 
-        if filename_lower.contains("american") {
-            "American Airlines".to_string()
-        } else if filename_lower.contains("southwest") {
-            "Southwest".to_string()
-        } else if filename_lower.contains("united") {
-            "United".to_string()
-        } else if filename_lower.contains("delta") || filename_lower.contains("dl-") {
-            "Delta".to_string()
-        } else {
-            "Unknown".to_string()
+        if let Some(physical) = self.client.get_alias_targets(ES_INDEX).await?.into_iter().next() {
+            return Ok(physical);
+        }
+
+        if self.client.index_exists(ES_INDEX).await? {
+            return Ok(ES_INDEX.to_string());
         }
+
+        anyhow::bail!(
+            "'{}' doesn't exist yet as an index or alias; run without --ingest-only first",
+            ES_INDEX
+        )
     }
 
-    fn get_pdf_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
-        if !path.exists() {
-            error!("Path '{:?}' does not exist", path);
-            return Ok(vec![]);
+    /// Atomically repoints the `ES_INDEX` alias at the index `create_index`
+    /// just filled, removing it from every index that previously held it
+    /// (normally one, but a prior run's failed cleanup can leave more than
+    /// one), then deletes those stale indices unless `--keep-old` was
+    /// passed. A no-op for a standalone `--ingest-only` run, since
+    /// `physical_index` is only set when this run created one.
+    async fn finalize_index_swap(&mut self) -> Result<()> {
+        let Some(new_physical) = self.physical_index.take() else {
+            return Ok(());
+        };
+
+        let old_physical = self.client.get_alias_targets(ES_INDEX).await?;
+
+        let mut actions = vec![json!({"add": {"index": new_physical, "alias": ES_INDEX}})];
+        for old in &old_physical {
+            if old != &new_physical {
+                actions.push(json!({"remove": {"index": old, "alias": ES_INDEX}}));
+            }
         }
+        self.client.update_aliases(actions).await?;
+        info!("Alias '{}' now points to '{}'", ES_INDEX, new_physical);
 
-        if path.is_file() {
-            if path.extension().and_then(|s| s.to_str()) == Some("pdf") {
-                return Ok(vec![path.to_path_buf()]);
-            } else {
-                error!("'{:?}' is not a PDF file", path);
-                return Ok(vec![]);
-            }
-        } else if path.is_dir() {
-            let mut pdf_files = Vec::new();
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_file()
-                    && path.extension().and_then(|s| s.to_str()) == Some("pdf")
-                {
-                    pdf_files.push(path);
+        if self.keep_old {
+            for old in &old_physical {
+                if old != &new_physical {
+                    info!("Keeping stale index '{}' (--keep-old)", old);
                 }
             }
-            pdf_files.sort();
-            if pdf_files.is_empty() {
-                warn!("No PDF files found in directory '{:?}'", path);
+        } else {
+            for old in &old_physical {
+                if old != &new_physical {
+                    match self.client.delete_index(old).await {
+                        Ok(true) => info!("Deleted stale index '{}'", old),
+                        Ok(false) => {}
+                        Err(e) => warn!("Failed to delete stale index '{}': {}", old, e),
+                    }
+                }
             }
-            return Ok(pdf_files);
         }
 
-        Ok(vec![])
+        Ok(())
     }
 
-    async fn index_pdf(&mut self, pdf_path: &Path) -> Result<bool> {
-        let filename = pdf_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown");
-        let airline = self.extract_airline_name(filename);
-
-        match fs::read(pdf_path) {
-            Ok(pdf_data) => {
-                let encoded_pdf = general_purpose::STANDARD.encode(&pdf_data);
-
-                let document = json!({
-                    "data": encoded_pdf,
-                    "filename": filename,
-                    "airline": airline
-                });
+    /// Spawns one `_bulk` flush of `batch` as a background task and adds it
+    /// to `bulk_tasks`, instead of awaiting it inline. Actual concurrent
+    /// requests are capped at `self.bulk_concurrency` by `semaphore`, which
+    /// every spawned task acquires a permit from before sending; this lets
+    /// PDF preparation keep filling the next batch while earlier batches are
+    /// still in flight (and retrying). The original `batch` is kept around
+    /// (not just its labels) so a whole-request failure can still be logged
+    /// and dead-lettered with full source payloads, not just filenames.
+    fn spawn_bulk_flush(
+        &self,
+        index_name: &str,
+        batch: Vec<(String, String, Value)>,
+        pipeline: Option<&str>,
+        semaphore: Arc<tokio::sync::Semaphore>,
+        bulk_tasks: &mut JoinSet<(Vec<(String, String, Value)>, Result<BulkIndexOutcome>, usize)>,
+    ) {
+        let client = Arc::clone(&self.client);
+        let index_name = index_name.to_string();
+        let pipeline = pipeline.map(|p| p.to_string());
+        let max_retries = self.max_retries;
+        let original = batch.clone();
+
+        bulk_tasks.spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("bulk concurrency semaphore should never be closed");
+            let (result, retries) = bulk_index_with_retry(
+                &client,
+                &index_name,
+                &batch,
+                pipeline.as_deref(),
+                max_retries,
+            )
+            .await;
+            (original, result, retries)
+        });
+    }
 
-                match self
-                    .client
-                    .index_document(ES_INDEX, &document, Some(PIPELINE_NAME))
-                    .await
-                {
-                    Ok(_) => {
-                        // Don't log here - progress is handled in ingest_pdfs()
-                        self.indexed_count += 1;
-                        Ok(true)
-                    }
-                    Err(e) => {
-                        error!("Error processing {}: {}", filename, e);
-                        // Log the full error chain for debugging
-                        if let Some(source) = e.source() {
-                            error!("  Caused by: {}", source);
-                        }
-                        Ok(false)
-                    }
+    /// Folds one finished bulk flush's outcome into the running totals
+    /// (indexed docs, per-item failures, whole-request failures) and returns
+    /// the documents that permanently failed, for the caller to append to
+    /// `--dead-letter` if one is configured.
+    fn fold_bulk_result(
+        &mut self,
+        batch: Vec<(String, String, Value)>,
+        result: Result<BulkIndexOutcome>,
+        retries: usize,
+        success_count: &mut usize,
+        failed_count: &mut usize,
+        failures: &mut Vec<Value>,
+    ) -> Vec<FailedDocument> {
+        match result {
+            Ok(outcome) => {
+                self.indexed_count += outcome.indexed;
+                *success_count += outcome.indexed;
+                if retries > 0 && outcome.indexed > 0 {
+                    self.retried_success_count += outcome.indexed;
                 }
+                for failed in &outcome.failed {
+                    error!(
+                        "[{}] {} ({}): {}",
+                        failed.id, failed.label, failed.error_type, failed.reason
+                    );
+                    failures.push(json!({
+                        "code": "document_rejected",
+                        "file": failed.label,
+                        "id": failed.id,
+                        "error_type": failed.error_type,
+                        "message": failed.reason,
+                    }));
+                }
+                self.permanently_failed_count += outcome.failed.len();
+                *failed_count += outcome.failed.len();
+                outcome.failed
             }
             Err(e) => {
-                error!("Error reading {}: {}", filename, e);
-                Ok(false)
+                error!(
+                    "Bulk request failed for {} document(s) after {} retr{}: {}",
+                    batch.len(),
+                    retries,
+                    if retries == 1 { "y" } else { "ies" },
+                    e
+                );
+                self.permanently_failed_count += batch.len();
+                *failed_count += batch.len();
+                let message = e.to_string();
+                let dead: Vec<FailedDocument> = batch
+                    .into_iter()
+                    .map(|(label, id, document)| FailedDocument {
+                        label: label.clone(),
+                        id,
+                        document,
+                        error_type: "bulk_request_failed".to_string(),
+                        reason: message.clone(),
+                    })
+                    .collect();
+                for failed in &dead {
+                    failures.push(json!({
+                        "code": "bulk_request_failed",
+                        "file": failed.label,
+                        "id": failed.id,
+                        "message": failed.reason,
+                    }));
+                }
+                dead
             }
         }
     }
 
-    async fn ingest_pdfs(&mut self, pdf_path: &Path) -> Result<bool> {
-        let pdf_files = self.get_pdf_files(pdf_path)?;
+    /// Appends (or, with `append: false`, replaces) a dead-letter NDJSON
+    /// file with one record per failed document: its id/filename, the ES
+    /// error, and the original source payload so `--retry-dead-letter` can
+    /// re-ingest it without re-reading the PDF. With `append: false` and no
+    /// documents left to record, the file is removed instead of left empty.
+    fn persist_dead_letter(path: &Path, failed: &[FailedDocument], append: bool) -> Result<()> {
+        if failed.is_empty() {
+            if !append && path.exists() {
+                fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove dead-letter file {:?}", path))?;
+            }
+            return Ok(());
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .with_context(|| format!("Failed to open dead-letter file {:?}", path))?;
+
+        for doc in failed {
+            let record = json!({
+                "id": doc.id,
+                "filename": doc.label,
+                "error_type": doc.error_type,
+                "reason": doc.reason,
+                "document": doc.document,
+            });
+            writeln!(file, "{}", record)
+                .with_context(|| format!("Failed to write to dead-letter file {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    async fn ingest_pdfs(&mut self, source: Arc<dyn PdfSource>) -> Result<bool> {
+        let pdf_files = source.list()?;
 
         if pdf_files.is_empty() {
             error!("No PDF files to process");
             return Ok(false);
         }
 
+        let write_index = self.resolve_write_index().await?;
+        info!("Writing into index: {}", write_index);
+
         let total_files = pdf_files.len();
         info!("Processing {} PDF file(s)...", total_files);
 
+        let mut checkpoint = match &self.checkpoint_path {
+            Some(path) => Some(CheckpointManifest::load(path)?),
+            None => None,
+        };
+
         let mut success_count = 0;
         let mut failed_count = 0;
+        let mut skipped_count = 0;
         let mut processed_count = 0;
+        let mut batch: Vec<(String, String, Value)> = Vec::new();
+        let mut batch_bytes = 0usize;
+        // Machine-readable failure entries (`code`, `file`, `message`),
+        // printed as one JSON summary at the end so CI can script against it
+        // instead of scraping the `error!` log lines.
+        let mut failures: Vec<Value> = Vec::new();
+
+        // Read and base64-encode up to `self.concurrency` PDFs at once; each
+        // file's disk I/O and encoding runs on a blocking-pool thread, and
+        // results are folded into the bulk batch in completion order rather
+        // than submission order.
+        let mut in_flight: JoinSet<Result<(String, String, Value)>> = JoinSet::new();
+        let mut remaining = pdf_files.into_iter();
+
+        // Bulk requests are dispatched onto their own task set as soon as a
+        // batch fills, instead of blocking PDF preparation until each one
+        // finishes (and, with `--max-retries`, until its retries finish
+        // too). `bulk_semaphore` caps how many of those requests are
+        // actually in flight at once.
+        let bulk_semaphore = Arc::new(tokio::sync::Semaphore::new(self.bulk_concurrency));
+        let mut bulk_tasks: JoinSet<(Vec<(String, String, Value)>, Result<BulkIndexOutcome>, usize)> =
+            JoinSet::new();
+
+        loop {
+            while in_flight.len() < self.concurrency {
+                match remaining.next() {
+                    Some(entry) => {
+                        let source = Arc::clone(&source);
+                        in_flight
+                            .spawn_blocking(move || prepare_pdf_document(source.as_ref(), &entry));
+                    }
+                    None => break,
+                }
+            }
 
-        for pdf_file in pdf_files {
-            if self.index_pdf(&pdf_file).await.unwrap_or(false) {
-                success_count += 1;
-            } else {
-                failed_count += 1;
+            let Some(result) = in_flight.join_next().await else {
+                break;
+            };
+
+            match result.context("PDF preparation task panicked")? {
+                Ok((filename, id, document)) => {
+                    let already_checkpointed = self.resume
+                        && checkpoint
+                            .as_ref()
+                            .is_some_and(|c| c.is_current(&filename, &id));
+                    if already_checkpointed {
+                        info!("Skipping {} (already indexed per checkpoint as {})", filename, id);
+                        skipped_count += 1;
+                    } else if self.skip_existing && self.client.document_exists(&write_index, &id).await? {
+                        info!("Skipping {} (unchanged, already indexed as {})", filename, id);
+                        skipped_count += 1;
+                    } else {
+                        let doc_bytes = serde_json::to_vec(&document).map(|v| v.len()).unwrap_or(0);
+                        if !batch.is_empty() && batch_bytes + doc_bytes > BULK_MAX_BYTES {
+                            let full_batch = std::mem::take(&mut batch);
+                            self.spawn_bulk_flush(
+                                &write_index,
+                                full_batch,
+                                Some(PIPELINE_NAME),
+                                Arc::clone(&bulk_semaphore),
+                                &mut bulk_tasks,
+                            );
+                            batch_bytes = 0;
+                        }
+                        batch_bytes += doc_bytes;
+                        batch.push((filename, id, document));
+                    }
+                }
+                Err(e) => {
+                    let err = ImportError::AttachmentExtractionFailed { message: e.to_string() };
+                    error!("[{}] {}", err.code(), e);
+                    failures.push(json!({"code": err.code(), "file": null, "message": e.to_string()}));
+                    failed_count += 1;
+                }
             }
-            
+
             processed_count += 1;
-            
-            // Update progress
+
+            // Update progress as each task finishes
             let percentage = (processed_count as f64 / total_files as f64 * 100.0 * 10.0).round() / 10.0;
             print!("\r{} of {} files processed ({:.1}%)", processed_count, total_files, percentage);
             std::io::stdout().flush().ok();
+
+            // Fold in any bulk flushes that have already completed, so
+            // progress doesn't silently pile up behind a slow/retrying
+            // request before being accounted for.
+            while let Some(done) = bulk_tasks.try_join_next() {
+                let (batch, result, retries) = done.context("bulk flush task panicked")?;
+                let ids: Vec<(String, String)> =
+                    batch.iter().map(|(l, i, _)| (l.clone(), i.clone())).collect();
+                let newly_failed = self.fold_bulk_result(
+                    batch,
+                    result,
+                    retries,
+                    &mut success_count,
+                    &mut failed_count,
+                    &mut failures,
+                );
+                if let Some(checkpoint) = checkpoint.as_mut() {
+                    record_checkpoint_successes(checkpoint, ids, &newly_failed);
+                }
+                if let Some(path) = self.dead_letter_path.clone() {
+                    Self::persist_dead_letter(&path, &newly_failed, true)?;
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            self.spawn_bulk_flush(&write_index, batch, Some(PIPELINE_NAME), Arc::clone(&bulk_semaphore), &mut bulk_tasks);
+        }
+
+        while let Some(done) = bulk_tasks.join_next().await {
+            let (batch, result, retries) = done.context("bulk flush task panicked")?;
+            let ids: Vec<(String, String)> =
+                batch.iter().map(|(l, i, _)| (l.clone(), i.clone())).collect();
+            let newly_failed = self.fold_bulk_result(
+                batch,
+                result,
+                retries,
+                &mut success_count,
+                &mut failed_count,
+                &mut failures,
+            );
+            if let Some(checkpoint) = checkpoint.as_mut() {
+                record_checkpoint_successes(checkpoint, ids, &newly_failed);
+            }
+            if let Some(path) = self.dead_letter_path.clone() {
+                Self::persist_dead_letter(&path, &newly_failed, true)?;
+            }
+        }
+
+        if let (Some(checkpoint), Some(path)) = (&checkpoint, &self.checkpoint_path) {
+            checkpoint.save(path)?;
         }
 
         // Print newline after progress line
         println!();
 
         info!("Indexed {} of {} file(s)", success_count, total_files);
+        if skipped_count > 0 {
+            info!("Skipped {} unchanged file(s)", skipped_count);
+        }
+        if self.retried_success_count > 0 {
+            info!(
+                "{} document(s) succeeded only after a retry",
+                self.retried_success_count
+            );
+        }
+        if failed_count > 0 {
+            warn!("Failed: {}", failed_count);
+            let summary = json!({"failed_count": failed_count, "failures": failures});
+            eprintln!("{}", summary);
+            if let Some(path) = &self.dead_letter_path {
+                warn!("Failed documents recorded in {:?}; retry with --retry-dead-letter", path);
+            }
+        }
+
+        Ok(failed_count == 0)
+    }
+
+    /// Streams a newline-delimited JSON file straight into the same
+    /// batching/bulk/retry/dead-letter machinery `ingest_pdfs` uses, one
+    /// line at a time -- the file is never read into memory all at once, so
+    /// this scales to NDJSON exports far bigger than the PDF corpus. Each
+    /// line is a pre-built document; an `"id"` field is used as the `_id`
+    /// if present, otherwise one is derived from a content hash of the line
+    /// (the same idempotent-reimport behavior `--skip-existing` relies on
+    /// for PDFs).
+    async fn ingest_ndjson(&mut self, path: &Path) -> Result<bool> {
+        let write_index = self.resolve_write_index().await?;
+        info!("Writing into index: {}", write_index);
+
+        let file = fs::File::open(path)
+            .with_context(|| format!("Failed to open NDJSON file {:?}", path))?;
+        let reader = std::io::BufReader::new(file);
+
+        let mut success_count = 0;
+        let mut failed_count = 0;
+        let mut skipped_count = 0;
+        let mut processed_count = 0;
+        let mut batch: Vec<(String, String, Value)> = Vec::new();
+        let mut batch_bytes = 0usize;
+        let mut failures: Vec<Value> = Vec::new();
+
+        let bulk_semaphore = Arc::new(tokio::sync::Semaphore::new(self.bulk_concurrency));
+        let mut bulk_tasks: JoinSet<(Vec<(String, String, Value)>, Result<BulkIndexOutcome>, usize)> =
+            JoinSet::new();
+
+        for (line_no, line) in std::io::BufRead::lines(reader).enumerate() {
+            let line = line.with_context(|| format!("Failed to read {:?} at line {}", path, line_no + 1))?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let document: Value = match serde_json::from_str(line) {
+                Ok(v) => v,
+                Err(e) => {
+                    let err = ImportError::AttachmentExtractionFailed {
+                        message: format!("invalid JSON at line {}: {}", line_no + 1, e),
+                    };
+                    error!("[{}] {}", err.code(), err);
+                    failures.push(json!({"code": err.code(), "file": null, "message": err.to_string()}));
+                    failed_count += 1;
+                    processed_count += 1;
+                    continue;
+                }
+            };
+
+            let label = document
+                .get("filename")
+                .or_else(|| document.get("id"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| format!("line {}", line_no + 1));
+            let id = document
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| content_id(line.as_bytes()));
+
+            if self.skip_existing && self.client.document_exists(&write_index, &id).await? {
+                info!("Skipping {} (unchanged, already indexed as {})", label, id);
+                skipped_count += 1;
+            } else {
+                let doc_bytes = line.len();
+                if !batch.is_empty() && batch_bytes + doc_bytes > BULK_MAX_BYTES {
+                    let full_batch = std::mem::take(&mut batch);
+                    self.spawn_bulk_flush(&write_index, full_batch, None, Arc::clone(&bulk_semaphore), &mut bulk_tasks);
+                    batch_bytes = 0;
+                }
+                batch_bytes += doc_bytes;
+                batch.push((label, id, document));
+            }
+
+            processed_count += 1;
+            if processed_count % 100 == 0 {
+                print!("\r{} line(s) processed", processed_count);
+                std::io::stdout().flush().ok();
+            }
+
+            // Nothing else in this loop awaits, so on the current-thread
+            // runtime the spawned flushes only make progress here: once
+            // `bulk_concurrency` are outstanding, wait for one to finish so
+            // batches don't pile up in memory ahead of the requests.
+            loop {
+                let done = if bulk_tasks.len() >= self.bulk_concurrency {
+                    bulk_tasks.join_next().await
+                } else {
+                    bulk_tasks.try_join_next()
+                };
+                let Some(done) = done else { break };
+                let (batch, result, retries) = done.context("bulk flush task panicked")?;
+                let newly_failed = self.fold_bulk_result(
+                    batch,
+                    result,
+                    retries,
+                    &mut success_count,
+                    &mut failed_count,
+                    &mut failures,
+                );
+                if let Some(dead_letter_path) = self.dead_letter_path.clone() {
+                    Self::persist_dead_letter(&dead_letter_path, &newly_failed, true)?;
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            self.spawn_bulk_flush(&write_index, batch, None, Arc::clone(&bulk_semaphore), &mut bulk_tasks);
+        }
+
+        while let Some(done) = bulk_tasks.join_next().await {
+            let (batch, result, retries) = done.context("bulk flush task panicked")?;
+            let newly_failed = self.fold_bulk_result(
+                batch,
+                result,
+                retries,
+                &mut success_count,
+                &mut failed_count,
+                &mut failures,
+            );
+            if let Some(dead_letter_path) = self.dead_letter_path.clone() {
+                Self::persist_dead_letter(&dead_letter_path, &newly_failed, true)?;
+            }
+        }
+
+        println!();
+        info!("Indexed {} document(s) from {:?}", success_count, path);
+        if skipped_count > 0 {
+            info!("Skipped {} unchanged document(s)", skipped_count);
+        }
+        if self.retried_success_count > 0 {
+            info!(
+                "{} document(s) succeeded only after a retry",
+                self.retried_success_count
+            );
+        }
         if failed_count > 0 {
             warn!("Failed: {}", failed_count);
+            let summary = json!({"failed_count": failed_count, "failures": failures});
+            eprintln!("{}", summary);
+            if let Some(dead_letter_path) = &self.dead_letter_path {
+                warn!("Failed documents recorded in {:?}; retry with --retry-dead-letter", dead_letter_path);
+            }
+        }
+
+        Ok(failed_count == 0)
+    }
+
+    /// Re-ingests only the documents recorded in a `--dead-letter` file,
+    /// instead of re-running PDF preparation from scratch. Successfully
+    /// retried records are dropped from the file; anything that still fails
+    /// (with its now-current error) remains so another retry can be tried
+    /// later, and the file is removed entirely once nothing is left.
+    async fn retry_dead_letter(&mut self, path: &Path) -> Result<bool> {
+        let write_index = self.resolve_write_index().await?;
+        info!("Retrying dead-lettered documents from {:?} into {}", path, write_index);
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read dead-letter file {:?}", path))?;
+
+        let mut records: Vec<(String, String, Value)> = Vec::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let record: Value = serde_json::from_str(line)
+                .with_context(|| format!("Invalid dead-letter record in {:?}", path))?;
+            let id = record
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let filename = record
+                .get("filename")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let document = record.get("document").cloned().unwrap_or_else(|| json!({}));
+            records.push((filename, id, document));
+        }
+
+        if records.is_empty() {
+            info!("Dead-letter file {:?} has no records to retry", path);
+            return Ok(true);
+        }
+
+        let total = records.len();
+        let bulk_semaphore = Arc::new(tokio::sync::Semaphore::new(self.bulk_concurrency));
+        let mut bulk_tasks: JoinSet<(Vec<(String, String, Value)>, Result<BulkIndexOutcome>, usize)> =
+            JoinSet::new();
+        let mut success_count = 0;
+        let mut failed_count = 0;
+        let mut failures: Vec<Value> = Vec::new();
+        let mut still_failing: Vec<FailedDocument> = Vec::new();
+        let mut batch: Vec<(String, String, Value)> = Vec::new();
+        let mut batch_bytes = 0usize;
+
+        for (filename, id, document) in records {
+            let doc_bytes = serde_json::to_vec(&document).map(|v| v.len()).unwrap_or(0);
+            if !batch.is_empty() && batch_bytes + doc_bytes > BULK_MAX_BYTES {
+                let full_batch = std::mem::take(&mut batch);
+                self.spawn_bulk_flush(&write_index, full_batch, Some(PIPELINE_NAME), Arc::clone(&bulk_semaphore), &mut bulk_tasks);
+                batch_bytes = 0;
+            }
+            batch_bytes += doc_bytes;
+            batch.push((filename, id, document));
+        }
+        if !batch.is_empty() {
+            self.spawn_bulk_flush(&write_index, batch, Some(PIPELINE_NAME), Arc::clone(&bulk_semaphore), &mut bulk_tasks);
+        }
+
+        while let Some(done) = bulk_tasks.join_next().await {
+            let (batch, result, retries) = done.context("bulk flush task panicked")?;
+            let newly_failed = self.fold_bulk_result(
+                batch,
+                result,
+                retries,
+                &mut success_count,
+                &mut failed_count,
+                &mut failures,
+            );
+            still_failing.extend(newly_failed);
+        }
+
+        info!("Retried {} of {} dead-lettered document(s)", success_count, total);
+        Self::persist_dead_letter(path, &still_failing, false)?;
+
+        if failed_count == 0 {
+            info!("All dead-lettered documents succeeded; removed {:?}", path);
+        } else {
+            warn!(
+                "{} document(s) still failing; see {:?} for the up-to-date list",
+                failed_count, path
+            );
         }
 
         Ok(failed_count == 0)
@@ -732,6 +1939,36 @@ impl ContractLoader {
                 warn!("Could not verify document count: {}", e);
             }
         }
+
+        if self.retried_success_count > 0 {
+            info!(
+                "{} document(s) only succeeded after a retry (transient errors)",
+                self.retried_success_count
+            );
+        }
+        if self.permanently_failed_count > 0 {
+            warn!(
+                "{} document(s) failed permanently (rejected per item, or in a bulk request that still failed after any retries)",
+                self.permanently_failed_count
+            );
+        }
+
+        if let Some(path) = &self.checkpoint_path {
+            let manifest = CheckpointManifest::load(path)?;
+            match self.client.count_documents(ES_INDEX).await {
+                Ok(count) if count != manifest.len() as u64 => {
+                    warn!(
+                        "Checkpoint manifest {:?} tracks {} file(s) but '{}' holds {} document(s); they may have drifted out of sync",
+                        path,
+                        manifest.len(),
+                        ES_INDEX,
+                        count
+                    );
+                }
+                _ => {}
+            }
+        }
+
         Ok(())
     }
 }
@@ -803,7 +2040,21 @@ async fn main() -> Result<()> {
     let mapping = load_json(&args.mapping)?;
 
     let inference_endpoint = args.inference_endpoint;
-    let mut loader = ContractLoader::new(client, mapping, inference_endpoint);
+    let embedder = EmbedderBackend::from_yaml(&config_data)?;
+    let mut loader = ContractLoader::new(
+        client,
+        mapping,
+        inference_endpoint,
+        embedder,
+        args.concurrency,
+        args.bulk_concurrency,
+        args.max_retries,
+        args.skip_existing,
+        args.keep_old,
+        args.dead_letter.clone(),
+        args.checkpoint.clone(),
+        args.resume,
+    );
 
     // Check Elasticsearch connection
     if !loader.check_elasticsearch().await? {
@@ -811,43 +2062,100 @@ async fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    // Setup phase
-    if !args.ingest_only {
-        // Check ELSER endpoint
-        if !loader.check_inference_endpoint().await? {
-            error!("ELSER inference endpoint not found!");
-            error!("Please deploy ELSER via Kibana or API before continuing.");
-            error!("See: Management → Machine Learning → Trained Models → ELSER → Deploy");
+    // --retry-dead-letter re-ingests previously failed documents straight
+    // into whatever index the alias currently points at; it skips the
+    // setup/PDF-discovery phases entirely since the documents are already
+    // prepared.
+    if let Some(path) = &args.retry_dead_letter {
+        if !loader.retry_dead_letter(path).await? {
+            error!("Some dead-lettered documents are still failing.");
             std::process::exit(1);
         }
+        return Ok(());
+    }
 
-        // Create pipeline
-        if !loader.create_pipeline().await? {
-            error!("Failed to create pipeline. Exiting.");
-            std::process::exit(1);
+    // Setup phase
+    if !args.ingest_only {
+        // `--input-format ndjson` documents are already fully built, so
+        // there's no attachment pipeline to run and no embedder to check --
+        // only the fresh physical index is needed.
+        if args.input_format == InputFormat::Pdf {
+            // Check the configured embedder (ELSER by default, or an HTTP
+            // backend from `embedder:`)
+            if !loader.check_inference_endpoint().await? {
+                match &loader.embedder {
+                    EmbedderBackend::Elser => {
+                        error!("ELSER inference endpoint not found!");
+                        error!("Please deploy ELSER via Kibana or API before continuing.");
+                        error!("See: Management → Machine Learning → Trained Models → ELSER → Deploy");
+                    }
+                    EmbedderBackend::Http(cfg) => {
+                        error!(
+                            "Could not reach the {} embedder at {}.",
+                            cfg.kind.label(),
+                            cfg.base_url
+                        );
+                        error!("Check embedder.base_url in the config and that the service is running.");
+                    }
+                }
+                std::process::exit(1);
+            }
+
+            // Create pipeline
+            if !loader.create_pipeline().await? {
+                error!("Failed to create pipeline. Exiting.");
+                std::process::exit(1);
+            }
         }
 
-        // Create index (will delete existing one if present)
+        // Create a fresh contracts-<timestamp> index behind the scenes; the
+        // `ES_INDEX` alias keeps serving the previous one until ingestion
+        // (below, or a later --ingest-only run) is ready to cut over.
         if !loader.create_index().await? {
             error!("Failed to create index. Exiting.");
             std::process::exit(1);
         }
+
+        // A standalone --setup-only run has no ingestion phase to swap the
+        // alias after, so cut over to the (still empty) new index now.
+        if args.setup_only {
+            loader.finalize_index_swap().await?;
+        }
     }
 
     // Ingestion phase
     if !args.setup_only {
         let ingestion_start = std::time::Instant::now();
 
-        let pdf_path = args.pdf_path.unwrap_or_else(|| {
-            resolve_with_project_fallback(Path::new("data"))
-                .unwrap_or_else(|_| PathBuf::from("data"))
-        });
+        let ok = match args.input_format {
+            InputFormat::Pdf => {
+                let pdf_path = args.pdf_path.unwrap_or_else(|| {
+                    resolve_with_project_fallback(Path::new("data"))
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|_| "data".to_string())
+                });
+                let s3_config = S3SourceConfig::from_yaml(&config_data);
+                let source: Arc<dyn PdfSource> = Arc::from(resolve_pdf_source(&pdf_path, s3_config)?);
+                loader.ingest_pdfs(source).await?
+            }
+            InputFormat::Ndjson => {
+                let ndjson_path = args
+                    .pdf_path
+                    .ok_or_else(|| anyhow::anyhow!("--input-format ndjson requires --pdf-path <file.jsonl>"))?;
+                loader.ingest_ndjson(Path::new(&ndjson_path)).await?
+            }
+        };
 
-        if !loader.ingest_pdfs(&pdf_path).await? {
-            error!("PDF ingestion had errors.");
+        if !ok {
+            error!("Ingestion had errors.");
             std::process::exit(1);
         }
 
+        // Only swaps the alias if this run also created a new physical
+        // index above; a plain --ingest-only run already wrote into the
+        // live one and has nothing to cut over.
+        loader.finalize_index_swap().await?;
+
         let elapsed = ingestion_start.elapsed();
         info!("Total ingestion time: {:.2} seconds", elapsed.as_secs_f64());
 
@@ -3,7 +3,7 @@ use clap::Parser;
 use csv::ReaderBuilder;
 use elasticsearch::{
     auth::Credentials,
-    cat::CatIndicesParts,
+    cat::{CatAliasesParts, CatIndicesParts},
     cert::CertificateValidation,
     cluster::ClusterHealthParts,
     http::{
@@ -11,18 +11,31 @@ use elasticsearch::{
         transport::{SingleNodeConnectionPool, TransportBuilder},
         Url,
     },
-    indices::{IndicesCreateParts, IndicesDeleteParts, IndicesExistsParts},
+    indices::{
+        IndicesCreateParts, IndicesDeleteParts, IndicesExistsParts, IndicesGetAliasParts,
+        IndicesGetMappingParts, IndicesUpdateAliasesParts,
+    },
     params::Refresh,
-    BulkParts, Elasticsearch,
+    snapshot::{SnapshotCreateParts, SnapshotCreateRepositoryParts, SnapshotRestoreParts},
+    BulkParts, Elasticsearch, ReindexParts,
 };
 use flate2::read::GzDecoder;
 use serde_json::{json, Value};
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Write};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tar::Archive as TarArchive;
+use tokio::task::JoinSet;
 use zip::ZipArchive;
 
+mod datasource;
+use datasource::{ObjectStore, ObjectStoreConfig, SourceLocation};
+
 // Simple logging macros
 macro_rules! info {
     ($($arg:tt)*) => {
@@ -46,6 +59,32 @@ macro_rules! debug {
 
 const BATCH_SIZE: usize = 500;
 
+/// Every file suffix `import_file` knows how to dispatch on, longest/most
+/// specific first so `--all`/`--glob`-style discovery doesn't miss a format
+/// just because it isn't a bare `.csv`/`.zip`.
+const ALL_FILE_SUFFIXES: &[&str] = &[
+    ".csv.gz",
+    ".csv",
+    ".ndjson",
+    ".jsonl",
+    ".json",
+    ".tar.gz",
+    ".tgz",
+    ".tar",
+    ".zip",
+    ".gz",
+];
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum IndexMode {
+    /// Delete and recreate the index before every import (current default behavior)
+    Recreate,
+    /// Create the index only if it's missing, and upsert documents by their deterministic FlightID
+    Upsert,
+    /// Create the index only if it's missing; documents are indexed without an explicit _id
+    CreateIfMissing,
+}
+
 #[derive(Parser)]
 #[command(name = "import_flights")]
 #[command(about = "Import flight data into Elasticsearch")]
@@ -56,11 +95,13 @@ struct Args {
     #[arg(short = 'm', long, default_value = "config/mappings-flights.json")]
     mapping: PathBuf,
 
+    /// Local directory or `s3://bucket/prefix` to read source files from
     #[arg(short = 'd', long, default_value = "data")]
-    data_dir: PathBuf,
+    data_dir: String,
 
+    /// Local path or `s3://bucket/key` of a single file to import
     #[arg(short = 'f', long, conflicts_with_all = ["all", "glob"])]
-    file: Option<PathBuf>,
+    file: Option<String>,
 
     #[arg(short = 'a', long, conflicts_with_all = ["file", "glob"])]
     all: bool,
@@ -74,6 +115,45 @@ struct Args {
     #[arg(long, default_value_t = BATCH_SIZE)]
     batch_size: usize,
 
+    /// Number of bulk requests allowed in flight at once; CSV parsing keeps feeding batches while these are outstanding
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// How to handle an already-loaded index: recreate (wipe), upsert (merge by FlightID), or create-if-missing
+    #[arg(long, value_enum, default_value_t = IndexMode::Recreate)]
+    mode: IndexMode,
+
+    /// Max retry attempts for bulk items rejected with a retryable error (429 / rejected_execution / 503)
+    #[arg(long, default_value_t = 5)]
+    max_retries: usize,
+
+    /// Write permanently-failed documents here as NDJSON so they can be re-imported later
+    #[arg(long)]
+    rejects_file: Option<PathBuf>,
+
+    /// Derive each document's _id from a hash of its (compacted) contents, so re-running an
+    /// interrupted import overwrites rather than duplicates. Ignored in --mode upsert, which
+    /// already has a deterministic _id via FlightID
+    #[arg(long)]
+    dedup: bool,
+
+    /// Comma-separated field list to hash for --dedup instead of the whole document, e.g.
+    /// "FlightDate,Reporting_Airline,Flight_Number" for logical (rather than byte-for-byte) dedup
+    #[arg(long, requires = "dedup")]
+    hash_fields: Option<String>,
+
+    /// Migrate any index whose mapping_version no longer matches the mapping file, without ingesting data
+    #[arg(long, conflicts_with_all = ["status", "delete_index", "delete_all", "sample"])]
+    migrate: bool,
+
+    /// Snapshot all flights-* indices under this name (registers the `snapshot_repository:` config first) and exit
+    #[arg(long, conflicts_with_all = ["status", "delete_index", "delete_all", "sample", "migrate", "restore"])]
+    snapshot: Option<String>,
+
+    /// Restore the named snapshot from the `snapshot_repository:` config and exit
+    #[arg(long, conflicts_with_all = ["status", "delete_index", "delete_all", "sample", "migrate", "snapshot"])]
+    restore: Option<String>,
+
     #[arg(long)]
     refresh: bool,
 
@@ -89,11 +169,13 @@ struct Args {
     #[arg(long, conflicts_with_all = ["delete_index", "delete_all", "status"])]
     sample: bool,
 
+    /// Local path or `s3://bucket/key` for the airports lookup file
     #[arg(long, default_value = "data/airports.csv.gz")]
-    airports_file: PathBuf,
+    airports_file: String,
 
+    /// Local path or `s3://bucket/key` for the cancellation codes lookup file
     #[arg(long, default_value = "data/cancellations.csv")]
-    cancellations_file: PathBuf,
+    cancellations_file: String,
 }
 
 #[derive(Debug, Clone)]
@@ -150,6 +232,58 @@ impl ElasticsearchConfig {
     }
 }
 
+/// Config for the filesystem or S3 repository that `--snapshot`/`--restore`
+/// register with Elasticsearch, read from the optional top-level
+/// `snapshot_repository:` section of the same config file.
+#[derive(Debug, Clone)]
+struct SnapshotRepoConfig {
+    name: String,
+    kind: String,
+    settings: Value,
+}
+
+impl SnapshotRepoConfig {
+    fn from_yaml(data: &Value) -> Option<Self> {
+        let section = data.get("snapshot_repository")?.as_object()?;
+
+        let name = section.get("name").and_then(|v| v.as_str())?.to_string();
+        let kind = section
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("fs")
+            .to_string();
+
+        let mut settings = section
+            .get("settings")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        if let Some(obj) = settings.as_object_mut() {
+            for key in ["location", "bucket", "base_path", "client", "region", "endpoint"] {
+                if !obj.contains_key(key) {
+                    if let Some(v) = section.get(key) {
+                        obj.insert(key.to_string(), v.clone());
+                    }
+                }
+            }
+        }
+
+        Some(Self {
+            name,
+            kind,
+            settings,
+        })
+    }
+}
+
+/// Outcome of a raw `_bulk` call. `Retryable` covers failures at the whole-request
+/// level (a dropped connection, or the cluster rejecting the entire batch with
+/// 429/503) as opposed to `Response`'s per-item `errors`/`items`, which `flush_batch`
+/// already retries individually.
+enum BulkOutcome {
+    Response(Value),
+    Retryable(String),
+}
+
 struct ElasticsearchClient {
     client: Elasticsearch,
 }
@@ -238,24 +372,38 @@ impl ElasticsearchClient {
         }
     }
 
-    async fn bulk(&self, lines: &[String], refresh: bool) -> Result<Value> {
+    /// Sends one `_bulk` request. A whole-request transport error or a
+    /// 429/503 status (the cluster rejecting the batch outright, as opposed
+    /// to individual items failing) comes back as `BulkOutcome::Retryable`
+    /// instead of an `Err`, so `flush_batch` can back off and resend the
+    /// same batch rather than aborting the import.
+    async fn bulk(&self, lines: &[String], refresh: bool) -> Result<BulkOutcome> {
         let refresh_val = if refresh { Refresh::True } else { Refresh::False };
 
-        let response = self
+        let response = match self
             .client
             .bulk(BulkParts::None)
             .body(lines.to_vec())
             .refresh(refresh_val)
             .send()
-            .await?;
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return Ok(BulkOutcome::Retryable(format!("transport error: {}", e))),
+        };
 
-        if !response.status_code().is_success() {
+        let status = response.status_code();
+        if status.as_u16() == 429 || status.as_u16() == 503 {
+            let text = response.text().await.unwrap_or_default();
+            return Ok(BulkOutcome::Retryable(text));
+        }
+        if !status.is_success() {
             let text = response.text().await?;
             anyhow::bail!("Bulk request failed: {}", text);
         }
 
         let result: Value = response.json().await?;
-        Ok(result)
+        Ok(BulkOutcome::Response(result))
     }
 
     async fn cluster_health(&self) -> Result<Value> {
@@ -312,6 +460,515 @@ impl ElasticsearchClient {
 
         Ok(deleted)
     }
+
+    /// Lists `(alias, physical_index)` pairs matching `pattern`, used by
+    /// `--migrate` to discover which logical indices exist.
+    async fn list_aliases(&self, pattern: &str) -> Result<Vec<(String, String)>> {
+        let response = self
+            .client
+            .cat()
+            .aliases(CatAliasesParts::Name(&[pattern]))
+            .format("json")
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Failed to list aliases: {}", text);
+        }
+
+        let parsed: Value = response.json().await?;
+        Ok(parsed
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|item| {
+                let alias = item.get("alias").and_then(|v| v.as_str())?;
+                let index = item.get("index").and_then(|v| v.as_str())?;
+                Some((alias.to_string(), index.to_string()))
+            })
+            .collect())
+    }
+
+    /// Resolves an alias to the single physical index backing it, or `None`
+    /// if `name` isn't an alias at all (e.g. it doesn't exist yet, or it's a
+    /// plain index from before aliasing was introduced).
+    async fn alias_target(&self, alias: &str) -> Result<Option<String>> {
+        let response = self
+            .client
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias]))
+            .send()
+            .await?;
+
+        if response.status_code().as_u16() == 404 {
+            return Ok(None);
+        }
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Failed to resolve alias '{}': {}", alias, text);
+        }
+
+        let parsed: Value = response.json().await?;
+        Ok(parsed
+            .as_object()
+            .and_then(|obj| obj.keys().next())
+            .map(|s| s.to_string()))
+    }
+
+    /// Reads `_meta.mapping_version` off an index's current mapping, if set.
+    async fn mapping_version_of(&self, index: &str) -> Result<Option<String>> {
+        let response = self
+            .client
+            .indices()
+            .get_mapping(IndicesGetMappingParts::Index(&[index]))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            return Ok(None);
+        }
+
+        let parsed: Value = response.json().await?;
+        Ok(parsed
+            .get(index)
+            .and_then(|v| v.get("mappings"))
+            .and_then(|v| v.get("_meta"))
+            .and_then(|v| v.get("mapping_version"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()))
+    }
+
+    async fn count_documents(&self, index: &str) -> Result<u64> {
+        let response = self
+            .client
+            .count(elasticsearch::CountParts::Index(&[index]))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            return Ok(0);
+        }
+
+        let result: Value = response.json().await?;
+        Ok(result.get("count").and_then(|v| v.as_u64()).unwrap_or(0))
+    }
+
+    /// Drives `_reindex` from `source` into `dest`, blocking until complete.
+    async fn reindex(&self, source: &str, dest: &str) -> Result<()> {
+        let response = self
+            .client
+            .reindex(ReindexParts::None)
+            .body(json!({
+                "source": {"index": source},
+                "dest": {"index": dest},
+            }))
+            .wait_for_completion(true)
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Reindex from '{}' to '{}' failed: {}", source, dest, text);
+        }
+
+        let result: Value = response.json().await?;
+        if let Some(failures) = result.get("failures").and_then(|v| v.as_array()) {
+            if !failures.is_empty() {
+                anyhow::bail!(
+                    "Reindex from '{}' to '{}' reported {} failure(s)",
+                    source,
+                    dest,
+                    failures.len()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Atomically repoints `alias` from `remove_index` (if any) to `add_index`.
+    async fn swap_alias(
+        &self,
+        alias: &str,
+        add_index: &str,
+        remove_index: Option<&str>,
+    ) -> Result<()> {
+        let mut actions = Vec::new();
+        if let Some(old_index) = remove_index {
+            actions.push(json!({"remove": {"index": old_index, "alias": alias}}));
+        }
+        actions.push(json!({"add": {"index": add_index, "alias": alias}}));
+
+        let response = self
+            .client
+            .indices()
+            .update_aliases(IndicesUpdateAliasesParts::None)
+            .body(json!({"actions": actions}))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!("Alias swap for '{}' failed: {}", alias, text);
+        }
+        Ok(())
+    }
+
+    /// Registers (or updates) the snapshot repository described by `repo`,
+    /// so `create_snapshot`/`restore_snapshot` have somewhere to read and write.
+    async fn ensure_snapshot_repository(&self, repo: &SnapshotRepoConfig) -> Result<()> {
+        let response = self
+            .client
+            .snapshot()
+            .create_repository(SnapshotCreateRepositoryParts::Repository(&repo.name))
+            .body(json!({
+                "type": repo.kind,
+                "settings": repo.settings,
+            }))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!(
+                "Failed to register snapshot repository '{}': {}",
+                repo.name,
+                text
+            );
+        }
+        Ok(())
+    }
+
+    /// Snapshots every `flights-*` index into `repo` under `snapshot`,
+    /// blocking until the snapshot completes.
+    async fn create_snapshot(&self, repo: &str, snapshot: &str) -> Result<()> {
+        let indices = self.list_indices("flights-*").await?;
+        if indices.is_empty() {
+            anyhow::bail!("No flights-* indices found to snapshot");
+        }
+
+        let response = self
+            .client
+            .snapshot()
+            .create(SnapshotCreateParts::RepositorySnapshot(repo, snapshot))
+            .wait_for_completion(true)
+            .body(json!({
+                "indices": indices.join(","),
+                "include_global_state": false,
+            }))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!(
+                "Snapshot '{}' in repository '{}' failed: {}",
+                snapshot,
+                repo,
+                text
+            );
+        }
+
+        let result: Value = response.json().await?;
+        let state = result
+            .get("snapshot")
+            .and_then(|v| v.get("state"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("UNKNOWN");
+        if state != "SUCCESS" {
+            anyhow::bail!("Snapshot '{}' finished in state {}", snapshot, state);
+        }
+
+        info!(
+            "Snapshot '{}' captured {} index(es): {}",
+            snapshot,
+            indices.len(),
+            indices.join(", ")
+        );
+        Ok(())
+    }
+
+    /// Restores `snapshot` from `repo`, blocking until every shard is back
+    /// online, and reports which indices came back.
+    async fn restore_snapshot(&self, repo: &str, snapshot: &str) -> Result<()> {
+        let response = self
+            .client
+            .snapshot()
+            .restore(SnapshotRestoreParts::RepositorySnapshot(repo, snapshot))
+            .wait_for_completion(true)
+            .body(json!({
+                "include_global_state": false,
+            }))
+            .send()
+            .await?;
+
+        if !response.status_code().is_success() {
+            let text = response.text().await?;
+            anyhow::bail!(
+                "Restore of snapshot '{}' from repository '{}' failed: {}",
+                snapshot,
+                repo,
+                text
+            );
+        }
+
+        let result: Value = response.json().await?;
+        let restored: Vec<String> = result
+            .get("snapshot")
+            .and_then(|v| v.get("indices"))
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        info!(
+            "Restored {} index(es) from snapshot '{}': {}",
+            restored.len(),
+            snapshot,
+            restored.join(", ")
+        );
+        Ok(())
+    }
+}
+
+/// Short, stable fingerprint of a mapping body. Embedded into a created
+/// index's `_meta.mapping_version` so a later run can tell whether the
+/// mapping on disk has drifted from what the index was built with.
+fn mapping_fingerprint(mapping: &Value) -> String {
+    let mut hasher = DefaultHasher::new();
+    mapping.to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Derives a stable bulk `_id` from the contents of a compacted document, so
+/// re-running an interrupted `--dedup` import overwrites the same documents
+/// instead of duplicating them. Keys are collected into a `BTreeMap` first so
+/// the hash is canonical regardless of CSV column order or serde_json's
+/// object iteration order. `hash_fields`, when given, narrows the hash to
+/// those fields for logical dedup (e.g. natural-key fields) rather than the
+/// full document.
+fn document_content_id(doc: &Value, hash_fields: Option<&[String]>) -> String {
+    let canonical: BTreeMap<&str, &Value> = match hash_fields {
+        Some(fields) => fields
+            .iter()
+            .filter_map(|field| doc.get(field.as_str()).map(|v| (field.as_str(), v)))
+            .collect(),
+        None => doc
+            .as_object()
+            .map(|map| map.iter().map(|(k, v)| (k.as_str(), v)).collect())
+            .unwrap_or_default(),
+    };
+    let canonical_bytes = serde_json::to_vec(&canonical).unwrap_or_default();
+
+    // A single 64-bit SipHash digest is too narrow to rule out collisions
+    // across a multi-million-row import, so two independent hashes (the
+    // second salted) are concatenated into a 128-bit digest instead of
+    // pulling in a dedicated 128-bit hasher crate.
+    let mut first = DefaultHasher::new();
+    canonical_bytes.hash(&mut first);
+    let mut second = DefaultHasher::new();
+    (&canonical_bytes, "document_content_id").hash(&mut second);
+
+    format!("{:016x}{:016x}", first.finish(), second.finish())
+}
+
+/// Parses a `flights-YYYY-MM`/`flights-YYYY`-shaped file or archive-entry name
+/// into its year/month parts, stripping compound extensions like `.csv.gz` or
+/// `.csv.zip` first. Shared by the outer archive name (`extract_year_month_from_filename`)
+/// and by each member path inside a `.tar`/`.tar.gz` archive, since a
+/// bundled archive's own name rarely carries the month. Members of a `.zip`
+/// archive take the outer archive name's year/month instead.
+fn year_month_from_name(name: &str) -> (Option<String>, Option<String>) {
+    let mut basename = Path::new(name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    // Strip compound extensions like .csv.gz or .csv.zip
+    loop {
+        if basename.ends_with(".csv") || basename.ends_with(".gz") || basename.ends_with(".zip") {
+            if let Some(stem) = Path::new(&basename).file_stem().and_then(|s| s.to_str()) {
+                basename = stem.to_string();
+                continue;
+            }
+        }
+        break;
+    }
+
+    // flights-YYYY-MM
+    if let Some((prefix, month_part)) = basename.rsplit_once('-') {
+        if month_part.len() == 2 && month_part.chars().all(|c| c.is_ascii_digit()) {
+            if let Some((_, year_part)) = prefix.rsplit_once('-') {
+                if year_part.len() == 4 && year_part.chars().all(|c| c.is_ascii_digit()) {
+                    return (Some(year_part.to_string()), Some(month_part.to_string()));
+                }
+            }
+        }
+    }
+
+    // flights-YYYY
+    if let Some((_, year_part)) = basename.rsplit_once('-') {
+        if year_part.len() == 4 && year_part.chars().all(|c| c.is_ascii_digit()) {
+            return (Some(year_part.to_string()), None);
+        }
+    }
+
+    (None, None)
+}
+
+/// Extracts the trailing `-vN` version number from a physical index name,
+/// e.g. `flights-2020-03-v2` -> `Some(2)`.
+fn parse_version_suffix(physical_index: &str) -> Option<usize> {
+    let (_, suffix) = physical_index.rsplit_once("-v")?;
+    suffix.parse().ok()
+}
+
+/// Flattens a JSON object's top-level fields into the same
+/// `HashMap<String, String>` shape `record_to_map` builds from a CSV row, so
+/// NDJSON/JSON-array sources can run through `transform_row` unmodified.
+/// Nested objects/arrays are stringified as-is; `transform_row` only reads
+/// scalar fields, so this just needs to round-trip those faithfully.
+fn json_row_to_map(value: &Value) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    if let Some(obj) = value.as_object() {
+        for (key, field) in obj {
+            if field.is_null() {
+                continue;
+            }
+            let text = match field {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            map.insert(key.clone(), text);
+        }
+    }
+    map
+}
+
+/// Streams elements out of a top-level JSON array one at a time, tracking
+/// bracket/quote depth to split on top-level commas, so a large `.json`
+/// export doesn't need to be buffered in full the way `serde_json::from_str`
+/// would require.
+struct JsonArrayReader<R: BufRead> {
+    reader: R,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> JsonArrayReader<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn skip_to_array_start(&mut self) -> Result<()> {
+        let mut byte = [0u8; 1];
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                anyhow::bail!("Expected a top-level JSON array but found end of input");
+            }
+            let c = byte[0] as char;
+            if c.is_whitespace() {
+                continue;
+            }
+            if c == '[' {
+                return Ok(());
+            }
+            anyhow::bail!("Expected a top-level JSON array, found '{}'", c);
+        }
+    }
+
+    fn next_element(&mut self) -> Result<Option<Value>> {
+        if self.finished {
+            return Ok(None);
+        }
+        if !self.started {
+            self.skip_to_array_start()?;
+            self.started = true;
+        }
+
+        let mut buf: Vec<u8> = Vec::new();
+        let mut depth = 0i32;
+        let mut in_string = false;
+        let mut escape = false;
+        let mut seen_any = false;
+        let mut byte = [0u8; 1];
+
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                if seen_any {
+                    anyhow::bail!("Unterminated JSON array");
+                }
+                self.finished = true;
+                return Ok(None);
+            }
+            let c = byte[0] as char;
+
+            if !in_string && !seen_any {
+                if c.is_whitespace() || c == ',' {
+                    continue;
+                }
+                if c == ']' {
+                    self.finished = true;
+                    return Ok(None);
+                }
+            }
+            seen_any = true;
+
+            if in_string {
+                buf.push(byte[0]);
+                if escape {
+                    escape = false;
+                } else if c == '\\' {
+                    escape = true;
+                } else if c == '"' {
+                    in_string = false;
+                }
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    in_string = true;
+                    buf.push(byte[0]);
+                }
+                '{' | '[' => {
+                    depth += 1;
+                    buf.push(byte[0]);
+                }
+                '}' | ']' if depth == 0 => {
+                    self.finished = true;
+                    break;
+                }
+                '}' | ']' => {
+                    depth -= 1;
+                    buf.push(byte[0]);
+                }
+                ',' if depth == 0 => break,
+                _ => buf.push(byte[0]),
+            }
+        }
+
+        if buf.is_empty() {
+            return Ok(None);
+        }
+
+        let value: Value = serde_json::from_slice(&buf).with_context(|| {
+            format!(
+                "Invalid JSON array element: {}",
+                String::from_utf8_lossy(&buf)
+            )
+        })?;
+        Ok(Some(value))
+    }
 }
 
 struct AirportLookup {
@@ -319,19 +976,21 @@ struct AirportLookup {
 }
 
 impl AirportLookup {
-    fn new(airports_file: &Path) -> Result<Self> {
+    fn new(location: &SourceLocation, store: Option<&ObjectStore>) -> Result<Self> {
         let mut airports = HashMap::new();
 
-        if !airports_file.exists() {
-            warn!("Airports file not found: {:?}", airports_file);
-            return Ok(Self { airports });
+        if let SourceLocation::Local(path) = location {
+            if !Path::new(path).exists() {
+                warn!("Airports file not found: {:?}", path);
+                return Ok(Self { airports });
+            }
         }
 
-        info!("Loading airports from {:?}", airports_file);
+        info!("Loading airports from {}", location.display());
         let mut count = 0;
 
-        let file = File::open(airports_file)?;
-        let decoder = GzDecoder::new(file);
+        let raw = datasource::open_source(location, store)?;
+        let decoder = GzDecoder::new(raw);
         let reader = BufReader::new(decoder);
         let mut csv_reader = ReaderBuilder::new()
             .has_headers(false)
@@ -377,19 +1036,21 @@ struct CancellationLookup {
 }
 
 impl CancellationLookup {
-    fn new(cancellations_file: &Path) -> Result<Self> {
+    fn new(location: &SourceLocation, store: Option<&ObjectStore>) -> Result<Self> {
         let mut cancellations = HashMap::new();
 
-        if !cancellations_file.exists() {
-            warn!("Cancellations file not found: {:?}", cancellations_file);
-            return Ok(Self { cancellations });
+        if let SourceLocation::Local(path) = location {
+            if !Path::new(path).exists() {
+                warn!("Cancellations file not found: {:?}", path);
+                return Ok(Self { cancellations });
+            }
         }
 
-        info!("Loading cancellations from {:?}", cancellations_file);
+        info!("Loading cancellations from {}", location.display());
         let mut count = 0;
 
-        let file = File::open(cancellations_file)?;
-        let reader = BufReader::new(file);
+        let raw = datasource::open_source(location, store)?;
+        let reader = BufReader::new(raw);
         let mut csv_reader = ReaderBuilder::new()
             .has_headers(true)
             .from_reader(reader);
@@ -421,7 +1082,7 @@ impl CancellationLookup {
 }
 
 struct FlightLoader {
-    client: ElasticsearchClient,
+    client: Arc<ElasticsearchClient>,
     mapping: Value,
     index_prefix: String,
     batch_size: usize,
@@ -429,8 +1090,19 @@ struct FlightLoader {
     airport_lookup: AirportLookup,
     cancellation_lookup: CancellationLookup,
     ensured_indices: HashSet<String>,
-    loaded_records: usize,
+    loaded_records: Arc<AtomicUsize>,
     total_records: usize,
+    failed_records: Arc<AtomicUsize>,
+    object_store: Option<ObjectStore>,
+    mode: IndexMode,
+    max_retries: usize,
+    rejects_writer: Option<Arc<Mutex<BufWriter<File>>>>,
+    dedup: bool,
+    hash_fields: Option<Vec<String>>,
+    concurrency: usize,
+    /// Bulk requests currently in flight, bounded to `concurrency` by
+    /// `dispatch_flush` before it spawns another one.
+    in_flight: JoinSet<Result<usize>>,
 }
 
 impl FlightLoader {
@@ -442,9 +1114,22 @@ impl FlightLoader {
         refresh: bool,
         airport_lookup: AirportLookup,
         cancellation_lookup: CancellationLookup,
-    ) -> Self {
-        Self {
-            client,
+        object_store: Option<ObjectStore>,
+        mode: IndexMode,
+        max_retries: usize,
+        rejects_file: Option<&Path>,
+        dedup: bool,
+        hash_fields: Option<Vec<String>>,
+        concurrency: usize,
+    ) -> Result<Self> {
+        let rejects_writer = rejects_file
+            .map(|path| -> Result<Arc<Mutex<BufWriter<File>>>> {
+                Ok(Arc::new(Mutex::new(BufWriter::new(File::create(path)?))))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            client: Arc::new(client),
             mapping,
             index_prefix: index,
             batch_size,
@@ -452,74 +1137,189 @@ impl FlightLoader {
             airport_lookup,
             cancellation_lookup,
             ensured_indices: HashSet::new(),
-            loaded_records: 0,
+            loaded_records: Arc::new(AtomicUsize::new(0)),
             total_records: 0,
-        }
+            failed_records: Arc::new(AtomicUsize::new(0)),
+            object_store,
+            mode,
+            max_retries,
+            rejects_writer,
+            dedup,
+            hash_fields,
+            concurrency: concurrency.max(1),
+            in_flight: JoinSet::new(),
+        })
     }
 
-    async fn ensure_index(&mut self, index_name: &str) -> Result<()> {
-        if self.ensured_indices.contains(index_name) {
-            debug!("Index {} already ensured in this session", index_name);
+    /// Ensures `logical_name` is a usable alias backed by an up-to-date
+    /// physical index, migrating data across to a new versioned index first
+    /// if the on-disk mapping has changed since the index was built. In
+    /// recreate mode a changed mapping is rebuilt empty instead of migrated;
+    /// outside recreate mode, a pre-existing plain (non-alias) index is left
+    /// as-is.
+    async fn ensure_index(&mut self, logical_name: &str) -> Result<()> {
+        if self.ensured_indices.contains(logical_name) {
+            debug!("Index {} already ensured in this session", logical_name);
             return Ok(());
         }
 
-        // Delete index if it exists before creating a new one
-        if self.client.index_exists(index_name).await? {
-            info!("Deleting existing index '{}' before import", index_name);
-            match self.client.delete_index(index_name).await {
-                Ok(true) => info!("Index '{}' deleted", index_name),
-                Ok(false) => warn!("Failed to delete index '{}'", index_name),
-                Err(e) => warn!("Error deleting index '{}': {}", index_name, e),
+        let fingerprint = mapping_fingerprint(&self.mapping);
+
+        match self.client.alias_target(logical_name).await? {
+            None => {
+                // No alias yet. `logical_name` may still exist as a plain,
+                // non-aliased index from before this versioning scheme.
+                if self.client.index_exists(logical_name).await? {
+                    if self.mode == IndexMode::Recreate {
+                        info!("Deleting legacy index '{}' before import", logical_name);
+                        self.client.delete_index(logical_name).await.ok();
+                    } else {
+                        debug!(
+                            "'{}' already exists as a plain index, leaving it as-is ({:?} mode)",
+                            logical_name, self.mode
+                        );
+                        self.ensured_indices.insert(logical_name.to_string());
+                        return Ok(());
+                    }
+                }
+
+                let physical = format!("{}-v1", logical_name);
+                info!("Creating index '{}' (alias '{}')", physical, logical_name);
+                self.client
+                    .create_index(&physical, &self.tagged_mapping(&fingerprint))
+                    .await?;
+                self.client.swap_alias(logical_name, &physical, None).await?;
+            }
+            Some(physical) => {
+                let current_version = self.client.mapping_version_of(&physical).await?;
+                if current_version.as_deref() == Some(fingerprint.as_str()) {
+                    if self.mode == IndexMode::Recreate {
+                        info!("Recreate mode: dropping existing data in '{}'", physical);
+                        self.client.delete_index(&physical).await.ok();
+                        self.client
+                            .create_index(&physical, &self.tagged_mapping(&fingerprint))
+                            .await?;
+                        self.client.swap_alias(logical_name, &physical, None).await?;
+                    }
+                } else if self.mode == IndexMode::Recreate {
+                    info!(
+                        "Mapping for '{}' changed (index '{}'); recreate mode, dropping and rebuilding instead of migrating",
+                        logical_name, physical
+                    );
+                    let next_version = parse_version_suffix(&physical).unwrap_or(1) + 1;
+                    let new_physical = format!("{}-v{}", logical_name, next_version);
+                    self.client
+                        .create_index(&new_physical, &self.tagged_mapping(&fingerprint))
+                        .await?;
+                    self.client
+                        .swap_alias(logical_name, &new_physical, Some(&physical))
+                        .await?;
+                    self.client.delete_index(&physical).await.ok();
+                } else {
+                    info!(
+                        "Mapping for '{}' changed (index '{}'); migrating to a new version",
+                        logical_name, physical
+                    );
+                    self.migrate_index(logical_name, &physical, &fingerprint)
+                        .await?;
+                }
             }
         }
 
-        info!("Creating index: {}", index_name);
-        self.client.create_index(index_name, &self.mapping).await?;
-        self.ensured_indices.insert(index_name.to_string());
+        self.ensured_indices.insert(logical_name.to_string());
         Ok(())
     }
 
-    fn extract_year_month_from_filename(
-        &self,
-        file_path: &Path,
-    ) -> (Option<String>, Option<String>) {
-        let mut basename = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        // Strip compound extensions like .csv.gz or .csv.zip
-        loop {
-            if basename.ends_with(".csv") || basename.ends_with(".gz") || basename.ends_with(".zip")
-            {
-                if let Some(stem) = Path::new(&basename).file_stem().and_then(|s| s.to_str()) {
-                    basename = stem.to_string();
-                    continue;
+    /// Embeds `fingerprint` as `_meta.mapping_version` into a copy of the
+    /// configured mapping body.
+    fn tagged_mapping(&self, fingerprint: &str) -> Value {
+        let mut mapping = self.mapping.clone();
+        if let Some(top) = mapping.as_object_mut() {
+            let mappings = top
+                .entry("mappings".to_string())
+                .or_insert_with(|| json!({}));
+            if let Some(mappings_obj) = mappings.as_object_mut() {
+                let meta = mappings_obj
+                    .entry("_meta".to_string())
+                    .or_insert_with(|| json!({}));
+                if let Some(meta_obj) = meta.as_object_mut() {
+                    meta_obj.insert("mapping_version".to_string(), json!(fingerprint));
                 }
             }
-            break;
         }
+        mapping
+    }
 
-        // flights-YYYY-MM
-        if let Some((prefix, month_part)) = basename.rsplit_once('-') {
-            if month_part.len() == 2 && month_part.chars().all(|c| c.is_ascii_digit()) {
-                if let Some((_, year_part)) = prefix.rsplit_once('-') {
-                    if year_part.len() == 4 && year_part.chars().all(|c| c.is_ascii_digit()) {
-                        return (Some(year_part.to_string()), Some(month_part.to_string()));
-                    }
-                }
-            }
+    /// Creates the next versioned physical index, reindexes any existing
+    /// documents into it, then atomically repoints the alias and drops the
+    /// old index.
+    async fn migrate_index(
+        &mut self,
+        logical_name: &str,
+        old_physical: &str,
+        fingerprint: &str,
+    ) -> Result<()> {
+        let next_version = parse_version_suffix(old_physical).unwrap_or(1) + 1;
+        let new_physical = format!("{}-v{}", logical_name, next_version);
+
+        self.client
+            .create_index(&new_physical, &self.tagged_mapping(fingerprint))
+            .await?;
+
+        let doc_count = self.client.count_documents(old_physical).await.unwrap_or(0);
+        if doc_count > 0 {
+            info!(
+                "Reindexing {} document(s) from '{}' to '{}'",
+                doc_count, old_physical, new_physical
+            );
+            self.client.reindex(old_physical, &new_physical).await?;
         }
 
-        // flights-YYYY
-        if let Some((_, year_part)) = basename.rsplit_once('-') {
-            if year_part.len() == 4 && year_part.chars().all(|c| c.is_ascii_digit()) {
-                return (Some(year_part.to_string()), None);
+        self.client
+            .swap_alias(logical_name, &new_physical, Some(old_physical))
+            .await?;
+        self.client.delete_index(old_physical).await.ok();
+
+        info!(
+            "Migrated '{}': '{}' -> '{}'",
+            logical_name, old_physical, new_physical
+        );
+        Ok(())
+    }
+
+    /// Migrates every alias matching `{index_prefix}-*` whose backing index
+    /// was built from a different mapping than the one loaded from disk.
+    /// Used by `--migrate` to run the upgrade independently of ingestion.
+    async fn migrate_all(&mut self) -> Result<()> {
+        let pattern = format!("{}-*", self.index_prefix);
+        let aliases = self.client.list_aliases(&pattern).await?;
+        if aliases.is_empty() {
+            info!("No aliases found matching '{}'; nothing to migrate", pattern);
+            return Ok(());
+        }
+
+        let fingerprint = mapping_fingerprint(&self.mapping);
+        for (alias, physical) in aliases {
+            let current_version = self.client.mapping_version_of(&physical).await?;
+            if current_version.as_deref() == Some(fingerprint.as_str()) {
+                debug!("'{}' already on mapping version {}", alias, fingerprint);
+                continue;
             }
+            info!("Migrating '{}' ('{}') to the current mapping", alias, physical);
+            self.migrate_index(&alias, &physical, &fingerprint).await?;
         }
+        Ok(())
+    }
 
-        (None, None)
+    fn extract_year_month_from_filename(
+        &self,
+        location: &SourceLocation,
+    ) -> (Option<String>, Option<String>) {
+        let name = match location {
+            SourceLocation::Local(path) => path.clone(),
+            SourceLocation::S3 { key, .. } => key.clone(),
+        };
+        year_month_from_name(&name)
     }
 
     fn extract_index_name(
@@ -543,21 +1343,7 @@ impl FlightLoader {
             }
         }
 
-        None
-    }
-
-    fn format_number(&self, number: usize) -> String {
-        number.to_string()
-            .chars()
-            .rev()
-            .collect::<Vec<_>>()
-            .chunks(3)
-            .map(|chunk| chunk.iter().collect::<String>())
-            .collect::<Vec<_>>()
-            .join(",")
-            .chars()
-            .rev()
-            .collect()
+        None
     }
 
     fn record_to_map(
@@ -574,13 +1360,29 @@ impl FlightLoader {
         map
     }
 
-    fn count_lines_fast(&self, file_path: &Path) -> usize {
-        match self.count_lines(file_path) {
+    fn count_lines_fast(&self, location: &SourceLocation) -> usize {
+        match self.count_lines(location) {
+            Ok(n) => n,
+            Err(e) => {
+                warn!("Failed to count lines in {}: {}", location.display(), e);
+                0
+            }
+        }
+    }
+
+    /// A `.json` file is a single top-level array, not guaranteed to have
+    /// one element per line (it can be minified onto one line, or
+    /// pretty-printed across several per element), so the line-counting
+    /// heuristic `count_lines` uses for `.ndjson`/`.jsonl` would mis-report
+    /// progress here. Stream through with the same `JsonArrayReader` used
+    /// to import it and count actual elements instead.
+    fn count_json_array_elements_fast(&self, location: &SourceLocation) -> usize {
+        match self.count_json_array_elements(location) {
             Ok(n) => n,
             Err(e) => {
                 warn!(
-                    "Failed to count lines in {:?}: {}",
-                    file_path.file_name().unwrap_or_default(),
+                    "Failed to count array elements in {}: {}",
+                    location.display(),
                     e
                 );
                 0
@@ -588,29 +1390,56 @@ impl FlightLoader {
         }
     }
 
-    fn count_lines(&self, file_path: &Path) -> Result<usize> {
-        let ext = file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+    fn count_json_array_elements(&self, location: &SourceLocation) -> Result<usize> {
+        let raw = datasource::open_source(location, self.object_store.as_ref())?;
+        let mut array_reader = JsonArrayReader::new(BufReader::new(raw));
+        let mut count = 0;
+        while array_reader.next_element()?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
 
-        if ext == "zip" {
-            self.count_lines_in_zip(file_path)
-        } else if file_path
-            .to_string_lossy()
-            .to_lowercase()
-            .ends_with(".gz")
-        {
-            self.count_lines_in_gzip(file_path)
+    fn count_lines(&self, location: &SourceLocation) -> Result<usize> {
+        let name = location.display().to_lowercase();
+
+        if name.ends_with(".zip") {
+            self.count_lines_in_zip(location)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.count_lines_in_tar(GzDecoder::new(raw))
+        } else if name.ends_with(".tar") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.count_lines_in_tar(raw)
+        } else if name.ends_with(".gz") {
+            self.count_lines_in_gzip(location)
         } else {
-            self.count_lines_plain(file_path)
+            self.count_lines_plain(location)
+        }
+    }
+
+    fn count_lines_in_tar<R: Read>(&self, reader: R) -> Result<usize> {
+        let mut archive = TarArchive::new(reader);
+        let mut count = 0;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let entry_name = entry.path()?.to_string_lossy().to_lowercase();
+            if !entry_name.ends_with(".csv") {
+                continue;
+            }
+            let reader = BufReader::new(entry);
+            for line in reader.lines() {
+                if line.is_ok() {
+                    count += 1;
+                }
+            }
         }
+        Ok(count)
     }
 
-    fn count_lines_plain(&self, file_path: &Path) -> Result<usize> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
+    fn count_lines_plain(&self, location: &SourceLocation) -> Result<usize> {
+        let raw = datasource::open_source(location, self.object_store.as_ref())?;
+        let reader = BufReader::new(raw);
         let mut count = 0;
         for line in reader.lines() {
             if line.is_ok() {
@@ -620,9 +1449,9 @@ impl FlightLoader {
         Ok(count)
     }
 
-    fn count_lines_in_gzip(&self, file_path: &Path) -> Result<usize> {
-        let file = File::open(file_path)?;
-        let decoder = GzDecoder::new(file);
+    fn count_lines_in_gzip(&self, location: &SourceLocation) -> Result<usize> {
+        let raw = datasource::open_source(location, self.object_store.as_ref())?;
+        let decoder = GzDecoder::new(raw);
         let reader = BufReader::new(decoder);
         let mut count = 0;
         for line in reader.lines() {
@@ -633,40 +1462,61 @@ impl FlightLoader {
         Ok(count)
     }
 
-    fn first_csv_entry_index(&self, archive: &mut ZipArchive<File>) -> Result<usize> {
+    /// Indices of every `.csv` member in `archive`, in archive order, so
+    /// callers can import a monthly data bundle shipped as one multi-member
+    /// zip in a single invocation instead of only its first entry.
+    fn csv_entry_indices<R: Read + std::io::Seek>(
+        &self,
+        archive: &mut ZipArchive<R>,
+    ) -> Result<Vec<usize>> {
+        let mut indices = Vec::new();
         for i in 0..archive.len() {
             let name = archive.by_index(i)?.name().to_lowercase();
             if name.ends_with(".csv") {
-                return Ok(i);
+                indices.push(i);
             }
         }
-        anyhow::bail!("No CSV entry found in archive");
+        if indices.is_empty() {
+            anyhow::bail!("No CSV entry found in archive");
+        }
+        Ok(indices)
     }
 
-    fn count_lines_in_zip(&self, file_path: &Path) -> Result<usize> {
-        let file = File::open(file_path)?;
-        let mut archive = ZipArchive::new(file)?;
-        let entry_index = self.first_csv_entry_index(&mut archive)?;
-        let mut entry = archive.by_index(entry_index)?;
-        let reader = BufReader::new(&mut entry);
+    fn count_lines_in_zip(&self, location: &SourceLocation) -> Result<usize> {
+        let raw = datasource::open_seekable(location, self.object_store.as_ref())?;
+        let mut archive = ZipArchive::new(raw)?;
+        let entry_indices = self.csv_entry_indices(&mut archive)?;
 
         let mut count = 0;
-        for line in reader.lines() {
-            if line.is_ok() {
-                count += 1;
+        for entry_index in entry_indices {
+            let mut entry = archive.by_index(entry_index)?;
+            let reader = BufReader::new(&mut entry);
+            for line in reader.lines() {
+                if line.is_ok() {
+                    count += 1;
+                }
             }
         }
 
         Ok(count)
     }
 
-    fn count_total_records_fast(&self, files: &[PathBuf]) -> usize {
+    fn count_total_records_fast(&self, files: &[SourceLocation]) -> usize {
         let mut total = 0;
-        for file_path in files {
-            if file_path.is_file() {
-                let line_count = self.count_lines_fast(file_path);
-                total += line_count.saturating_sub(1); // Subtract 1 for CSV header
+        for location in files {
+            let name = location.display().to_lowercase();
+            if name.ends_with(".json") {
+                total += self.count_json_array_elements_fast(location);
+                continue;
             }
+
+            let line_count = self.count_lines_fast(location);
+            let has_header_row = !(name.ends_with(".ndjson") || name.ends_with(".jsonl"));
+            total += if has_header_row {
+                line_count.saturating_sub(1) // Subtract 1 for CSV header
+            } else {
+                line_count
+            };
         }
         total
     }
@@ -788,55 +1638,167 @@ impl FlightLoader {
         Value::Object(compacted)
     }
 
-    async fn flush(&mut self, lines: &[String], index_name: &str) -> Result<usize> {
-        let result = self
-            .client
-            .bulk(lines, self.refresh)
-            .await?;
+    /// Queues `lines` (alternating action/doc NDJSON pairs) for `index_name`
+    /// as a background `flush_batch` task, blocking only long enough to free
+    /// a slot if `concurrency` bulk requests are already in flight. Returns
+    /// the document count of any tasks drained to make room, so callers can
+    /// keep an accurate running total without waiting for every task.
+    async fn dispatch_flush(&mut self, lines: Vec<String>, index_name: String) -> Result<usize> {
+        let mut drained = 0;
+
+        // Surface a hard failure (a bulk request that errored at the HTTP
+        // level, not just a per-item rejection) from an already-finished
+        // task as soon as it's available, rather than only noticing it once
+        // the concurrency cap or end-of-file forces a join. This keeps the
+        // old single-threaded loader's fail-fast behavior intact: a dead
+        // cluster aborts the import within one `concurrency`-sized window
+        // instead of parsing silently ahead of it.
+        while let Some(result) = self.in_flight.try_join_next() {
+            drained += result??;
+        }
 
-        if let Some(errors) = result.get("errors").and_then(|v| v.as_bool()) {
-            if errors {
-                let empty: Vec<Value> = Vec::new();
-                let items = result
-                    .get("items")
-                    .and_then(|v| v.as_array())
-                    .unwrap_or(&empty);
-                let error_items: Vec<_> = items
-                    .iter()
-                    .filter_map(|item| {
-                        item.get("index")
-                            .and_then(|idx| idx.get("error"))
-                            .map(|e| e.to_string())
-                    })
-                    .take(5)
-                    .collect();
-
-                for error in &error_items {
-                    warn!("Bulk item error for {}: {}", index_name, error);
-                }
-                anyhow::bail!("Bulk indexing reported errors for {}; aborting", index_name);
+        while self.in_flight.len() >= self.concurrency {
+            if let Some(result) = self.in_flight.join_next().await {
+                drained += result??;
             }
         }
 
-        let doc_count = lines.len() / 2;
-        self.loaded_records += doc_count;
+        let client = Arc::clone(&self.client);
+        let refresh = self.refresh;
+        let max_retries = self.max_retries;
+        let rejects_writer = self.rejects_writer.clone();
+        let loaded_records = Arc::clone(&self.loaded_records);
+        let failed_records = Arc::clone(&self.failed_records);
+        let total_records = self.total_records;
 
-        if self.total_records > 0 {
-            let percentage = (self.loaded_records as f64 / self.total_records as f64 * 100.0 * 10.0)
-                .round()
-                / 10.0;
-            print!(
-                "\r{} of {} records loaded ({:.1}%)",
-                self.format_number(self.loaded_records),
-                self.format_number(self.total_records),
-                percentage
-            );
+        self.in_flight.spawn(flush_batch(
+            client,
+            refresh,
+            max_retries,
+            lines,
+            index_name,
+            rejects_writer,
+            loaded_records,
+            failed_records,
+            total_records,
+        ));
+
+        Ok(drained)
+    }
+
+    /// Awaits every outstanding `flush_batch` task, used once a file (or the
+    /// whole import) has no more batches left to produce.
+    async fn drain_flushes(&mut self) -> Result<usize> {
+        let mut drained = 0;
+        while let Some(result) = self.in_flight.join_next().await {
+            drained += result??;
+        }
+        Ok(drained)
+    }
+
+    /// Transforms and buffers one already-decoded row, flushing a batch via
+    /// `dispatch_flush` once `batch_size` is reached. Shared by the CSV,
+    /// NDJSON, and JSON-array ingestion paths so they all feed the same
+    /// `transform_row`/`compact_document`/bulk pipeline. Returns how many
+    /// documents were flushed as a side effect of this call (usually 0).
+    async fn process_row(
+        &mut self,
+        row_map: &HashMap<String, String>,
+        row_number: usize,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+        index_buffers: &mut HashMap<String, (Vec<String>, usize)>,
+    ) -> Result<usize> {
+        let mut doc = self.transform_row(row_map);
+
+        let timestamp_value = doc
+            .get("@timestamp")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let index_name =
+            match self.extract_index_name(timestamp_value.as_deref(), file_year, file_month) {
+                Some(name) => name,
+                None => {
+                    let timestamp_raw = row_map
+                        .get("@timestamp")
+                        .or_else(|| row_map.get("FlightDate"))
+                        .cloned()
+                        .unwrap_or_default();
+                    warn!(
+                        "Skipping document - missing or invalid timestamp. Raw value: {:?}. Row {}: Origin={:?}, Dest={:?}, Airline={:?}",
+                        timestamp_raw,
+                        row_number,
+                        row_map.get("Origin"),
+                        row_map.get("Dest"),
+                        row_map.get("Reporting_Airline")
+                    );
+                    return Ok(0);
+                }
+            };
+
+        doc = self.compact_document(doc);
+        if doc.as_object().map(|map| map.is_empty()).unwrap_or(true) {
+            return Ok(0);
+        }
+
+        self.ensure_index(&index_name).await?;
+
+        let buffer = index_buffers
+            .entry(index_name.clone())
+            .or_insert_with(|| (Vec::new(), 0));
+
+        let doc_id = if self.mode == IndexMode::Upsert {
+            let id = doc.get("FlightID").and_then(|v| v.as_str()).map(String::from);
+            if id.is_none() {
+                warn!(
+                    "Upsert mode but row {} has no FlightID (Origin={:?}, Dest={:?}, Airline={:?}); indexing with an auto-generated _id instead of upserting",
+                    row_number,
+                    row_map.get("Origin"),
+                    row_map.get("Dest"),
+                    row_map.get("Reporting_Airline")
+                );
+            }
+            id
+        } else if self.dedup {
+            Some(document_content_id(&doc, self.hash_fields.as_deref()))
         } else {
-            print!("\r{} records loaded", self.format_number(self.loaded_records));
+            None
+        };
+        let action = match doc_id {
+            Some(id) => json!({"index": {"_index": index_name, "_id": id}}),
+            None => json!({"index": {"_index": index_name}}),
+        };
+        buffer.0.push(serde_json::to_string(&action)?);
+        buffer.0.push(serde_json::to_string(&doc)?);
+        buffer.1 += 1;
+
+        if buffer.1 >= self.batch_size {
+            let batch = std::mem::take(&mut buffer.0);
+            buffer.1 = 0;
+            return self.dispatch_flush(batch, index_name).await;
         }
-        std::io::stdout().flush().ok();
 
-        Ok(doc_count)
+        Ok(0)
+    }
+
+    /// Flushes whatever is left in every per-index buffer, then waits for
+    /// every outstanding `flush_batch` task, once a source has no more rows
+    /// left to produce.
+    async fn drain_index_buffers(
+        &mut self,
+        index_buffers: &mut HashMap<String, (Vec<String>, usize)>,
+    ) -> Result<usize> {
+        let mut indexed_docs = 0;
+        for (index_name, (lines, count)) in index_buffers.iter_mut() {
+            if *count > 0 {
+                let batch = std::mem::take(lines);
+                *count = 0;
+                indexed_docs += self.dispatch_flush(batch, index_name.clone()).await?;
+            }
+        }
+        indexed_docs += self.drain_flushes().await?;
+        Ok(indexed_docs)
     }
 
     async fn process_reader<R: Read>(
@@ -870,135 +1832,195 @@ impl FlightLoader {
             }
 
             let row_map = self.record_to_map(&headers, &record);
-            let mut doc = self.transform_row(&row_map);
+            indexed_docs += self
+                .process_row(&row_map, processed_rows, file_year, file_month, &mut index_buffers)
+                .await?;
+        }
 
-            let timestamp_value = doc
-                .get("@timestamp")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
+        indexed_docs += self.drain_index_buffers(&mut index_buffers).await?;
 
-            let index_name = self.extract_index_name(
-                timestamp_value.as_deref(),
-                file_year,
-                file_month,
-            );
+        Ok((processed_rows, indexed_docs))
+    }
 
-            if index_name.is_none() {
-                let timestamp_raw = row_map
-                    .get("@timestamp")
-                    .or_else(|| row_map.get("FlightDate"))
-                    .cloned()
-                    .unwrap_or_default();
-                warn!(
-                    "Skipping document - missing or invalid timestamp. Raw value: {:?}. Row {}: Origin={:?}, Dest={:?}, Airline={:?}",
-                    timestamp_raw,
-                    processed_rows,
-                    row_map.get("Origin"),
-                    row_map.get("Dest"),
-                    row_map.get("Reporting_Airline")
-                );
-                continue;
-            }
-            let index_name = index_name.unwrap();
-
-            doc = self.compact_document(doc);
-            if doc
-                .as_object()
-                .map(|map| map.is_empty())
-                .unwrap_or(true)
-            {
+    /// Ingests one JSON object per line (`.ndjson`/`.jsonl`), skipping blank
+    /// lines, through the same per-row pipeline as the CSV path.
+    async fn process_json_lines<R: Read>(
+        &mut self,
+        reader: R,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+    ) -> Result<(usize, usize)> {
+        let reader = BufReader::new(reader);
+        let mut processed_rows = 0;
+        let mut indexed_docs = 0;
+        let mut index_buffers: HashMap<String, (Vec<String>, usize)> = HashMap::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
                 continue;
             }
 
-            self.ensure_index(&index_name).await?;
+            processed_rows += 1;
+            let value: Value = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid NDJSON on row {}", processed_rows))?;
+            let row_map = json_row_to_map(&value);
+            indexed_docs += self
+                .process_row(&row_map, processed_rows, file_year, file_month, &mut index_buffers)
+                .await?;
+        }
 
-            let buffer = index_buffers
-                .entry(index_name.clone())
-                .or_insert_with(|| (Vec::new(), 0));
+        indexed_docs += self.drain_index_buffers(&mut index_buffers).await?;
 
-            buffer
-                .0
-                .push(serde_json::to_string(&json!({"index": {"_index": index_name}}))?);
-            buffer.0.push(serde_json::to_string(&doc)?);
-            buffer.1 += 1;
+        Ok((processed_rows, indexed_docs))
+    }
 
-            if buffer.1 >= self.batch_size {
-                let docs_in_batch = self.flush(&buffer.0, &index_name).await?;
-                indexed_docs += docs_in_batch;
-                buffer.0.clear();
-                buffer.1 = 0;
-            }
+    /// Ingests a top-level JSON array (`.json`) one element at a time via
+    /// `JsonArrayReader`, through the same per-row pipeline as the CSV path.
+    async fn process_json_array<R: Read>(
+        &mut self,
+        reader: R,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+    ) -> Result<(usize, usize)> {
+        let mut array_reader = JsonArrayReader::new(BufReader::new(reader));
+        let mut processed_rows = 0;
+        let mut indexed_docs = 0;
+        let mut index_buffers: HashMap<String, (Vec<String>, usize)> = HashMap::new();
+
+        while let Some(value) = array_reader.next_element()? {
+            processed_rows += 1;
+            let row_map = json_row_to_map(&value);
+            indexed_docs += self
+                .process_row(&row_map, processed_rows, file_year, file_month, &mut index_buffers)
+                .await?;
         }
 
-        for (index_name, (lines, count)) in index_buffers.iter_mut() {
-            if *count > 0 {
-                let docs_in_batch = self.flush(lines, index_name).await?;
-                indexed_docs += docs_in_batch;
-                lines.clear();
-                *count = 0;
+        indexed_docs += self.drain_index_buffers(&mut index_buffers).await?;
+
+        Ok((processed_rows, indexed_docs))
+    }
+
+    /// Imports every `.csv` member of a `tar`/`tar.gz` archive, deriving each
+    /// member's `file_year`/`file_month` from its own path (via
+    /// `year_month_from_name`) rather than the outer archive name, since a
+    /// monthly bundle's members usually carry the date and the archive
+    /// itself often doesn't (e.g. `flights-2024.tar.gz` containing
+    /// `flights-2024-01.csv`, `flights-2024-02.csv`, ...).
+    async fn process_tar_entries<R: Read>(&mut self, reader: R) -> Result<(usize, usize)> {
+        let mut archive = TarArchive::new(reader);
+        let mut processed_rows = 0;
+        let mut indexed_docs = 0;
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_name = entry.path()?.to_string_lossy().to_string();
+            if !entry_name.to_lowercase().ends_with(".csv") {
+                continue;
             }
+
+            let (entry_year, entry_month) = year_month_from_name(&entry_name);
+            let (rows, docs) = self
+                .process_reader(&mut entry, entry_year.as_deref(), entry_month.as_deref())
+                .await?;
+            processed_rows += rows;
+            indexed_docs += docs;
         }
 
         Ok((processed_rows, indexed_docs))
     }
 
-    async fn import_file(&mut self, file_path: &Path) -> Result<()> {
-        if !file_path.is_file() {
-            warn!("Skipping {:?} (not a regular file)", file_path);
-            return Ok(());
+    async fn import_file(&mut self, location: &SourceLocation) -> Result<()> {
+        if let SourceLocation::Local(path) = location {
+            if !Path::new(path).is_file() {
+                warn!("Skipping {:?} (not a regular file)", path);
+                return Ok(());
+            }
         }
 
-        let (file_year, file_month) = self.extract_year_month_from_filename(file_path);
-        info!("Importing {:?}", file_path);
-
-        let results = if file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .eq_ignore_ascii_case("zip")
-        {
-            let file = File::open(file_path)?;
-            let mut archive = ZipArchive::new(file)?;
-            let entry_index = self.first_csv_entry_index(&mut archive)?;
-            let entry = archive.by_index(entry_index)?;
-            self.process_reader(
-                entry,
-                file_year.as_deref(),
-                file_month.as_deref(),
-            )
-            .await?
-        } else if file_path
-            .to_string_lossy()
-            .to_lowercase()
-            .ends_with(".gz")
-        {
-            let file = File::open(file_path)?;
-            let decoder = GzDecoder::new(file);
-            self.process_reader(
-                decoder,
-                file_year.as_deref(),
-                file_month.as_deref(),
-            )
-            .await?
+        let (file_year, file_month) = self.extract_year_month_from_filename(location);
+        info!("Importing {}", location.display());
+
+        let name = location.display().to_lowercase();
+        let results = if name.ends_with(".ndjson") || name.ends_with(".jsonl") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.process_json_lines(raw, file_year.as_deref(), file_month.as_deref())
+                .await?
+        } else if name.ends_with(".json") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.process_json_array(raw, file_year.as_deref(), file_month.as_deref())
+                .await?
+        } else if name.ends_with(".zip") {
+            let raw = datasource::open_seekable(location, self.object_store.as_ref())?;
+            let mut archive = ZipArchive::new(raw)?;
+            let entry_indices = self.csv_entry_indices(&mut archive)?;
+
+            let mut processed_rows = 0;
+            let mut indexed_docs = 0;
+            for entry_index in entry_indices {
+                let entry = archive.by_index(entry_index)?;
+                let (rows, docs) = self
+                    .process_reader(entry, file_year.as_deref(), file_month.as_deref())
+                    .await?;
+                processed_rows += rows;
+                indexed_docs += docs;
+            }
+            (processed_rows, indexed_docs)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            let decoder = GzDecoder::new(raw);
+            self.process_tar_entries(decoder).await?
+        } else if name.ends_with(".tar") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.process_tar_entries(raw).await?
+        } else if name.ends_with(".gz") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            let decoder = GzDecoder::new(raw);
+            self.process_reader(decoder, file_year.as_deref(), file_month.as_deref())
+                .await?
         } else {
-            let file = File::open(file_path)?;
-            self.process_reader(
-                file,
-                file_year.as_deref(),
-                file_month.as_deref(),
-            )
-            .await?
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            self.process_reader(raw, file_year.as_deref(), file_month.as_deref())
+                .await?
         };
 
         let (processed_rows, indexed_docs) = results;
         info!(
-            "Finished {:?} (rows processed: {}, documents indexed: {})",
-            file_path, processed_rows, indexed_docs
+            "Finished {} (rows processed: {}, documents indexed: {})",
+            location.display(),
+            processed_rows,
+            indexed_docs
         );
 
         Ok(())
     }
 
+    /// Transforms, compacts, and annotates a single row for `--sample`,
+    /// shared by the CSV, NDJSON, and JSON-array sampling paths.
+    fn row_to_sample(
+        &self,
+        row_map: &HashMap<String, String>,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+    ) -> Value {
+        let doc = self.transform_row(row_map);
+        let compacted = self.compact_document(doc);
+
+        // Include derived index name for debugging if we can determine it
+        let timestamp_value = compacted
+            .get("@timestamp")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let index_name =
+            self.extract_index_name(timestamp_value.as_deref(), file_year, file_month);
+
+        let mut output = compacted;
+        if let Some(index_name) = index_name {
+            output["__index"] = json!(index_name);
+        }
+        output
+    }
+
     fn sample_from_reader<R: Read>(
         &self,
         reader: R,
@@ -1014,68 +2036,113 @@ impl FlightLoader {
 
         if csv_reader.read_record(&mut record)? {
             let row_map = self.record_to_map(&headers, &record);
-            let doc = self.transform_row(&row_map);
-            let compacted = self.compact_document(doc);
-
-            // Include derived index name for debugging if we can determine it
-            let timestamp_value = compacted
-                .get("@timestamp")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-            let index_name =
-                self.extract_index_name(timestamp_value.as_deref(), file_year, file_month);
-
-            let mut output = compacted;
-            if let Some(index_name) = index_name {
-                output["__index"] = json!(index_name);
+            return Ok(Some(self.row_to_sample(&row_map, file_year, file_month)));
+        }
+
+        Ok(None)
+    }
+
+    fn sample_from_json_lines<R: Read>(
+        &self,
+        reader: R,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+    ) -> Result<Option<Value>> {
+        let reader = BufReader::new(reader);
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
             }
-            return Ok(Some(output));
+            let value: Value = serde_json::from_str(&line).context("Invalid NDJSON line")?;
+            let row_map = json_row_to_map(&value);
+            return Ok(Some(self.row_to_sample(&row_map, file_year, file_month)));
         }
 
         Ok(None)
     }
 
-    fn sample_document(&self, file_path: &Path) -> Result<Option<Value>> {
-        if !file_path.is_file() {
-            warn!("Skipping {:?} (not a regular file)", file_path);
-            return Ok(None);
+    fn sample_from_json_array<R: Read>(
+        &self,
+        reader: R,
+        file_year: Option<&str>,
+        file_month: Option<&str>,
+    ) -> Result<Option<Value>> {
+        let mut array_reader = JsonArrayReader::new(BufReader::new(reader));
+        if let Some(value) = array_reader.next_element()? {
+            let row_map = json_row_to_map(&value);
+            return Ok(Some(self.row_to_sample(&row_map, file_year, file_month)));
         }
 
-        let (file_year, file_month) = self.extract_year_month_from_filename(file_path);
+        Ok(None)
+    }
 
-        if file_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .eq_ignore_ascii_case("zip")
-        {
-            let file = File::open(file_path)?;
-            let mut archive = ZipArchive::new(file)?;
-            let entry_index = self.first_csv_entry_index(&mut archive)?;
+    fn sample_from_tar_entries<R: Read>(&self, reader: R) -> Result<Option<Value>> {
+        let mut archive = TarArchive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_name = entry.path()?.to_string_lossy().to_string();
+            if !entry_name.to_lowercase().ends_with(".csv") {
+                continue;
+            }
+            let (entry_year, entry_month) = year_month_from_name(&entry_name);
+            return self.sample_from_reader(&mut entry, entry_year.as_deref(), entry_month.as_deref());
+        }
+        Ok(None)
+    }
+
+    fn sample_document(&self, location: &SourceLocation) -> Result<Option<Value>> {
+        if let SourceLocation::Local(path) = location {
+            if !Path::new(path).is_file() {
+                warn!("Skipping {:?} (not a regular file)", path);
+                return Ok(None);
+            }
+        }
+
+        let (file_year, file_month) = self.extract_year_month_from_filename(location);
+        let name = location.display().to_lowercase();
+
+        if name.ends_with(".ndjson") || name.ends_with(".jsonl") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            return self.sample_from_json_lines(raw, file_year.as_deref(), file_month.as_deref());
+        } else if name.ends_with(".json") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            return self.sample_from_json_array(raw, file_year.as_deref(), file_month.as_deref());
+        } else if name.ends_with(".zip") {
+            let raw = datasource::open_seekable(location, self.object_store.as_ref())?;
+            let mut archive = ZipArchive::new(raw)?;
+            let entry_index = self.csv_entry_indices(&mut archive)?[0];
             let entry = archive.by_index(entry_index)?;
             return self.sample_from_reader(entry, file_year.as_deref(), file_month.as_deref());
-        } else if file_path
-            .to_string_lossy()
-            .to_lowercase()
-            .ends_with(".gz")
-        {
-            let file = File::open(file_path)?;
-            let decoder = GzDecoder::new(file);
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            let decoder = GzDecoder::new(raw);
+            return self.sample_from_tar_entries(decoder);
+        } else if name.ends_with(".tar") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            return self.sample_from_tar_entries(raw);
+        } else if name.ends_with(".gz") {
+            let raw = datasource::open_source(location, self.object_store.as_ref())?;
+            let decoder = GzDecoder::new(raw);
             return self.sample_from_reader(decoder, file_year.as_deref(), file_month.as_deref());
         }
 
-        let file = File::open(file_path)?;
-        self.sample_from_reader(file, file_year.as_deref(), file_month.as_deref())
+        let raw = datasource::open_source(location, self.object_store.as_ref())?;
+        self.sample_from_reader(raw, file_year.as_deref(), file_month.as_deref())
     }
 
-    async fn import_files(&mut self, files: &[PathBuf]) -> Result<()> {
+    async fn import_files(&mut self, files: &[SourceLocation]) -> Result<()> {
         info!("Counting records in {} file(s)...", files.len());
         self.total_records = self.count_total_records_fast(files);
         info!(
             "Total records to import: {}",
-            self.format_number(self.total_records)
+            format_number(self.total_records)
+        );
+        info!(
+            "Importing {} file(s) with up to {} bulk request(s) in flight...",
+            files.len(),
+            self.concurrency
         );
-        info!("Importing {} file(s)...", files.len());
 
         for file_path in files {
             self.import_file(file_path).await?;
@@ -1083,15 +2150,206 @@ impl FlightLoader {
 
         println!();
         info!(
-            "Import complete: {} of {} records loaded",
-            self.format_number(self.loaded_records),
-            self.format_number(self.total_records)
+            "Import complete: {} succeeded, {} permanently failed (of {} total)",
+            format_number(self.loaded_records.load(Ordering::Relaxed)),
+            format_number(self.failed_records.load(Ordering::Relaxed)),
+            format_number(self.total_records)
         );
+        if let Some(writer) = self.rejects_writer.as_ref() {
+            writer.lock().unwrap().flush().ok();
+        }
 
         Ok(())
     }
 }
 
+/// True for bulk item errors Elasticsearch expects a client to retry:
+/// write-queue rejections (429 / `es_rejected_execution_exception`) and
+/// transient 503s from an overloaded node.
+fn is_retryable_bulk_error(error: &Value, status: Option<u64>) -> bool {
+    if matches!(status, Some(429) | Some(503)) {
+        return true;
+    }
+    error
+        .get("type")
+        .and_then(|v| v.as_str())
+        .map(|t| t == "es_rejected_execution_exception")
+        .unwrap_or(false)
+}
+
+/// Exponential backoff with jitter: 1s, 2s, 4s, ... capped at 30s, minus up
+/// to a quarter of the cap in jitter so retries from a batch don't all land
+/// on the same instant.
+fn retry_backoff_ms(attempt: usize) -> u64 {
+    const BASE_MS: u64 = 1_000;
+    const CAP_MS: u64 = 30_000;
+
+    let capped = BASE_MS.saturating_mul(1u64 << attempt.min(10)).min(CAP_MS);
+    let jitter_range = capped / 4;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let jitter = if jitter_range > 0 {
+        nanos % jitter_range
+    } else {
+        0
+    };
+    capped.saturating_sub(jitter)
+}
+
+fn format_number(number: usize) -> String {
+    number.to_string()
+        .chars()
+        .rev()
+        .collect::<Vec<_>>()
+        .chunks(3)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join(",")
+        .chars()
+        .rev()
+        .collect()
+}
+
+/// Bulk-indexes one batch in its own task so `FlightLoader::dispatch_flush`
+/// can keep several requests in flight at once; see its doc comment for the
+/// concurrency bound. Resends the whole batch when the request itself fails
+/// transiently (`BulkOutcome::Retryable`), otherwise retries only the items
+/// Elasticsearch flagged as retryable (429 /
+/// `es_rejected_execution_exception` / 503), both with exponential backoff
+/// and jitter, and counts/writes out anything that fails permanently.
+#[allow(clippy::too_many_arguments)]
+async fn flush_batch(
+    client: Arc<ElasticsearchClient>,
+    refresh: bool,
+    max_retries: usize,
+    lines: Vec<String>,
+    index_name: String,
+    rejects_writer: Option<Arc<Mutex<BufWriter<File>>>>,
+    loaded_records: Arc<AtomicUsize>,
+    failed_records: Arc<AtomicUsize>,
+    total_records: usize,
+) -> Result<usize> {
+    let mut pairs: Vec<(String, String)> = lines
+        .chunks(2)
+        .filter(|chunk| chunk.len() == 2)
+        .map(|chunk| (chunk[0].clone(), chunk[1].clone()))
+        .collect();
+
+    let mut succeeded = 0usize;
+    let mut attempt = 0usize;
+
+    while !pairs.is_empty() {
+        let batch_lines: Vec<String> = pairs
+            .iter()
+            .flat_map(|(action, doc)| [action.clone(), doc.clone()])
+            .collect();
+
+        let result = match client.bulk(&batch_lines, refresh).await? {
+            BulkOutcome::Response(value) => value,
+            BulkOutcome::Retryable(reason) => {
+                if attempt >= max_retries {
+                    anyhow::bail!(
+                        "Bulk request for {} failed after {} retries: {}",
+                        index_name,
+                        max_retries,
+                        reason
+                    );
+                }
+                attempt += 1;
+                let backoff_ms = retry_backoff_ms(attempt);
+                warn!(
+                    "Bulk request for {} failed transiently ({}), retrying whole batch of {} item(s) after {}ms (attempt {}/{})",
+                    index_name,
+                    reason,
+                    pairs.len(),
+                    backoff_ms,
+                    attempt,
+                    max_retries
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                continue;
+            }
+        };
+        let has_errors = result
+            .get("errors")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !has_errors {
+            succeeded += pairs.len();
+            break;
+        }
+
+        let empty: Vec<Value> = Vec::new();
+        let items = result
+            .get("items")
+            .and_then(|v| v.as_array())
+            .unwrap_or(&empty);
+
+        let mut retry_pairs = Vec::new();
+        for (item, (action, doc)) in items.iter().zip(pairs.iter()) {
+            let action_result = item.get("index");
+            match action_result.and_then(|a| a.get("error")) {
+                None => succeeded += 1,
+                Some(error) => {
+                    let status = action_result
+                        .and_then(|a| a.get("status"))
+                        .and_then(|v| v.as_u64());
+                    if attempt < max_retries && is_retryable_bulk_error(error, status) {
+                        retry_pairs.push((action.clone(), doc.clone()));
+                    } else {
+                        failed_records.fetch_add(1, Ordering::Relaxed);
+                        warn!("Bulk item permanently failed for {}: {}", index_name, error);
+                        if let Some(writer) = rejects_writer.as_ref() {
+                            let mut writer = writer.lock().unwrap();
+                            if let Err(e) = writeln!(writer, "{}", doc) {
+                                warn!("Failed to write rejected document: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if retry_pairs.is_empty() {
+            break;
+        }
+
+        attempt += 1;
+        let backoff_ms = retry_backoff_ms(attempt);
+        debug!(
+            "Retrying {} bulk item(s) for {} after {}ms (attempt {}/{})",
+            retry_pairs.len(),
+            index_name,
+            backoff_ms,
+            attempt,
+            max_retries
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+        pairs = retry_pairs;
+    }
+
+    let doc_count = succeeded;
+    let total_loaded = loaded_records.fetch_add(doc_count, Ordering::Relaxed) + doc_count;
+
+    if total_records > 0 {
+        let percentage = (total_loaded as f64 / total_records as f64 * 100.0 * 10.0).round() / 10.0;
+        print!(
+            "\r{} of {} records loaded ({:.1}%)",
+            format_number(total_loaded),
+            format_number(total_records),
+            percentage
+        );
+    } else {
+        print!("\r{} records loaded", format_number(total_loaded));
+    }
+    std::io::stdout().flush().ok();
+
+    Ok(doc_count)
+}
+
 fn load_yaml(path: &Path) -> Result<Value> {
     let resolved = resolve_with_project_fallback(path)
         .with_context(|| format!("Config file not found: {:?}", path))?;
@@ -1160,19 +2418,69 @@ fn resolve_file_path(path: &Path, data_dir: &Path) -> Result<PathBuf> {
     anyhow::bail!("File not found: {:?}", path);
 }
 
-fn files_to_process(args: &Args) -> Result<Vec<PathBuf>> {
+/// Resolves a `--airports-file`/`--cancellations-file` argument to a
+/// `SourceLocation`, leaving `s3://` references untouched and falling back to
+/// the project-relative search used for local config/data files otherwise.
+fn resolve_lookup_location(raw: &str, has_object_store: bool) -> Result<SourceLocation> {
+    let loc = SourceLocation::parse(raw);
+    match loc {
+        SourceLocation::S3 { .. } if !has_object_store => {
+            anyhow::bail!("{} requires an [s3] section in the Elasticsearch config", raw)
+        }
+        SourceLocation::S3 { .. } => Ok(loc),
+        SourceLocation::Local(path) => match resolve_with_project_fallback(Path::new(&path)) {
+            Ok(resolved) => Ok(SourceLocation::Local(resolved.to_string_lossy().to_string())),
+            Err(_) => Ok(SourceLocation::Local(path)),
+        },
+    }
+}
+
+fn files_to_process(args: &Args, store: Option<&ObjectStore>) -> Result<Vec<SourceLocation>> {
+    let data_dir_loc = SourceLocation::parse(&args.data_dir);
+
     if let Some(file) = &args.file {
-        return Ok(vec![resolve_file_path(file, &args.data_dir)?]);
+        let loc = SourceLocation::parse(file);
+        if let SourceLocation::Local(path) = &loc {
+            let resolved = resolve_file_path(Path::new(path), &local_data_dir(&data_dir_loc))?;
+            return Ok(vec![SourceLocation::Local(
+                resolved.to_string_lossy().to_string(),
+            )]);
+        }
+        return Ok(vec![loc]);
     }
 
     if args.all {
+        if let SourceLocation::S3 { bucket, key } = &data_dir_loc {
+            let store = store.ok_or_else(|| {
+                anyhow::anyhow!("--data-dir is s3://... but no [s3] section found in config")
+            })?;
+            let mut keys = store.list(bucket, key)?;
+            keys.retain(|k| {
+                let lower = k.to_lowercase();
+                ALL_FILE_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix))
+            });
+            keys.sort();
+            if keys.is_empty() {
+                anyhow::bail!(
+                    "No {} objects found under s3://{}/{}",
+                    ALL_FILE_SUFFIXES.join(", "),
+                    bucket,
+                    key
+                );
+            }
+            return Ok(keys
+                .into_iter()
+                .map(|k| SourceLocation::S3 {
+                    bucket: bucket.clone(),
+                    key: k,
+                })
+                .collect());
+        }
+
+        let data_dir = local_data_dir(&data_dir_loc);
         let mut files = Vec::new();
-        for pattern in &["*.zip", "*.csv", "*.csv.gz"] {
-            let full_pattern = if args.data_dir.is_absolute() {
-                format!("{}/{}", args.data_dir.display(), pattern)
-            } else {
-                format!("{}/{}", args.data_dir.display(), pattern)
-            };
+        for suffix in ALL_FILE_SUFFIXES {
+            let full_pattern = format!("{}/*{}", data_dir.display(), suffix);
             for entry in glob::glob(&full_pattern)? {
                 if let Ok(path) = entry {
                     if path.is_file() {
@@ -1182,16 +2490,44 @@ fn files_to_process(args: &Args) -> Result<Vec<PathBuf>> {
             }
         }
         files.sort();
+        files.dedup();
         if files.is_empty() {
             anyhow::bail!(
-                "No .zip, .csv, or .csv.gz files found in {:?}",
-                args.data_dir
+                "No {} files found in {:?}",
+                ALL_FILE_SUFFIXES.join(", "),
+                data_dir
             );
         }
-        return Ok(files);
+        return Ok(files
+            .into_iter()
+            .map(|p| SourceLocation::Local(p.to_string_lossy().to_string()))
+            .collect());
     }
 
     if let Some(glob_pattern) = &args.glob {
+        if let SourceLocation::S3 { bucket, .. } = &data_dir_loc {
+            let store = store.ok_or_else(|| {
+                anyhow::anyhow!("--data-dir is s3://... but no [s3] section found in config")
+            })?;
+            let mut keys = store.list(bucket, glob_pattern)?;
+            keys.sort();
+            if keys.is_empty() {
+                anyhow::bail!(
+                    "No objects found matching prefix {} in s3://{}",
+                    glob_pattern,
+                    bucket
+                );
+            }
+            return Ok(keys
+                .into_iter()
+                .map(|k| SourceLocation::S3 {
+                    bucket: bucket.clone(),
+                    key: k,
+                })
+                .collect());
+        }
+
+        let data_dir = local_data_dir(&data_dir_loc);
         let mut files = Vec::new();
         let pattern = if Path::new(glob_pattern).is_absolute() {
             glob_pattern.clone()
@@ -1208,7 +2544,7 @@ fn files_to_process(args: &Args) -> Result<Vec<PathBuf>> {
             }
             if !found {
                 // Try relative to data_dir
-                format!("{}/{}", args.data_dir.display(), glob_pattern)
+                format!("{}/{}", data_dir.display(), glob_pattern)
             } else {
                 glob_pattern.clone()
             }
@@ -1228,12 +2564,24 @@ fn files_to_process(args: &Args) -> Result<Vec<PathBuf>> {
         if files.is_empty() {
             anyhow::bail!("No files found matching pattern: {}", glob_pattern);
         }
-        return Ok(files);
+        return Ok(files
+            .into_iter()
+            .map(|p| SourceLocation::Local(p.to_string_lossy().to_string()))
+            .collect());
     }
 
     anyhow::bail!("Please provide either --file PATH, --all, or --glob PATTERN");
 }
 
+/// Local on-disk data dir, even when `--data-dir` points at an S3 prefix
+/// (used as the fallback base for resolving locally-given `--file` args).
+fn local_data_dir(data_dir_loc: &SourceLocation) -> PathBuf {
+    match data_dir_loc {
+        SourceLocation::Local(path) => PathBuf::from(path),
+        SourceLocation::S3 { .. } => PathBuf::from("data"),
+    }
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     let start_time = std::time::Instant::now();
@@ -1295,11 +2643,39 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args.snapshot.is_some() || args.restore.is_some() {
+        let repo = SnapshotRepoConfig::from_yaml(&config_data).ok_or_else(|| {
+            anyhow::anyhow!(
+                "A `snapshot_repository:` section (name + type) is required in the config to use --snapshot/--restore"
+            )
+        })?;
+        client.ensure_snapshot_repository(&repo).await?;
+
+        if let Some(name) = &args.snapshot {
+            client.create_snapshot(&repo.name, name).await?;
+        } else if let Some(name) = &args.restore {
+            client.restore_snapshot(&repo.name, name).await?;
+        }
+        return Ok(());
+    }
+
+    let object_store = ObjectStoreConfig::from_yaml(&config_data)
+        .map(ObjectStore::new)
+        .transpose()?;
+
+    let airports_loc = resolve_lookup_location(&args.airports_file, object_store.is_some())?;
+    let cancellations_loc =
+        resolve_lookup_location(&args.cancellations_file, object_store.is_some())?;
+
     let mapping = load_json(&args.mapping)?;
-    let airports_path = resolve_with_project_fallback(&args.airports_file)?;
-    let cancellations_path = resolve_with_project_fallback(&args.cancellations_file)?;
-    let airport_lookup = AirportLookup::new(&airports_path)?;
-    let cancellation_lookup = CancellationLookup::new(&cancellations_path)?;
+    let airport_lookup = AirportLookup::new(&airports_loc, object_store.as_ref())?;
+    let cancellation_lookup = CancellationLookup::new(&cancellations_loc, object_store.as_ref())?;
+
+    let files = if args.migrate {
+        Vec::new()
+    } else {
+        files_to_process(&args, object_store.as_ref())?
+    };
 
     let mut loader = FlightLoader::new(
         client,
@@ -1309,9 +2685,22 @@ async fn main() -> Result<()> {
         args.refresh,
         airport_lookup,
         cancellation_lookup,
-    );
+        object_store,
+        args.mode,
+        args.max_retries,
+        args.rejects_file.as_deref(),
+        args.dedup,
+        args.hash_fields
+            .as_deref()
+            .map(|fields| fields.split(',').map(|f| f.trim().to_string()).collect()),
+        args.concurrency,
+    )?;
+
+    if args.migrate {
+        loader.migrate_all().await?;
+        return Ok(());
+    }
 
-    let files = files_to_process(&args)?;
     if args.sample {
         let file = files
             .first()
@@ -0,0 +1,325 @@
+// Pluggable sources for the PDFs `import_contracts` ingests: a local
+// directory (the original behavior), an S3-compatible bucket, or a set of
+// plain HTTP(S) URLs listed in a manifest file. This mirrors the local/S3
+// split in `main.rs`'s `datasource.rs`, but `import_contracts` is a separate
+// binary that doesn't share modules with `main.rs` (each binary in this
+// crate duplicates its own copies rather than pulling in a shared lib), so
+// this is its own minimal version scoped to fetching PDF bytes.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// One PDF a `PdfSource` knows how to fetch.
+#[derive(Debug, Clone)]
+pub enum SourceRef {
+    Local(PathBuf),
+    S3 { bucket: String, key: String },
+    Http(String),
+}
+
+impl SourceRef {
+    /// A short label used for the `filename` document field and progress lines.
+    pub fn label(&self) -> String {
+        let raw = match self {
+            SourceRef::Local(path) => path.file_name().and_then(|n| n.to_str()),
+            SourceRef::S3 { key, .. } => key.rsplit('/').next(),
+            SourceRef::Http(url) => url.rsplit('/').next().filter(|s| !s.is_empty()),
+        };
+        raw.unwrap_or("unknown").to_string()
+    }
+}
+
+/// Enumerates PDFs and fetches their raw bytes, regardless of where they live.
+pub trait PdfSource: Send + Sync {
+    fn list(&self) -> Result<Vec<SourceRef>>;
+    fn read(&self, entry: &SourceRef) -> Result<Vec<u8>>;
+}
+
+/// PDFs under a local file or directory -- the original `fs::read` behavior.
+pub struct LocalPdfSource {
+    path: PathBuf,
+}
+
+impl LocalPdfSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl PdfSource for LocalPdfSource {
+    fn list(&self) -> Result<Vec<SourceRef>> {
+        let path = &self.path;
+        if !path.exists() {
+            anyhow::bail!("Path '{:?}' does not exist", path);
+        }
+
+        if path.is_file() {
+            if path.extension().and_then(|s| s.to_str()) == Some("pdf") {
+                return Ok(vec![SourceRef::Local(path.clone())]);
+            }
+            anyhow::bail!("'{:?}' is not a PDF file", path);
+        }
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            if entry_path.is_file() && entry_path.extension().and_then(|s| s.to_str()) == Some("pdf")
+            {
+                files.push(entry_path);
+            }
+        }
+        files.sort();
+        Ok(files.into_iter().map(SourceRef::Local).collect())
+    }
+
+    fn read(&self, entry: &SourceRef) -> Result<Vec<u8>> {
+        match entry {
+            SourceRef::Local(path) => {
+                fs::read(path).with_context(|| format!("Error reading {:?}", path))
+            }
+            _ => anyhow::bail!("LocalPdfSource was handed a non-local entry"),
+        }
+    }
+}
+
+/// Config for the optional `s3:` section of `config/elasticsearch.yml`,
+/// reused verbatim from `datasource::ObjectStoreConfig`'s parsing.
+#[derive(Debug, Clone, Default)]
+pub struct S3SourceConfig {
+    pub endpoint: String,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub ssl_verify: bool,
+}
+
+impl S3SourceConfig {
+    pub fn from_yaml(data: &Value) -> Option<Self> {
+        let section = data.get("s3")?.as_object()?;
+
+        let normalize = |v: Option<&str>| -> Option<String> {
+            v.map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+
+        let endpoint = normalize(section.get("endpoint").and_then(|v| v.as_str()))?;
+        let access_key = normalize(section.get("access_key").and_then(|v| v.as_str()));
+        let secret_key = normalize(section.get("secret_key").and_then(|v| v.as_str()));
+        let ssl_verify = section
+            .get("ssl_verify")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        Some(Self {
+            endpoint,
+            access_key,
+            secret_key,
+            ssl_verify,
+        })
+    }
+}
+
+/// PDFs under a prefix in an S3-compatible bucket, listed via the same
+/// ListObjectsV2 XML API `datasource::ObjectStore` uses. Only the first page
+/// of the listing is read; continuation tokens are not followed.
+pub struct S3PdfSource {
+    config: S3SourceConfig,
+    bucket: String,
+    prefix: String,
+    client: reqwest::blocking::Client,
+}
+
+impl S3PdfSource {
+    pub fn new(config: S3SourceConfig, bucket: String, prefix: String) -> Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .danger_accept_invalid_certs(!config.ssl_verify)
+            .build()
+            .context("Failed to build object storage HTTP client")?;
+        Ok(Self {
+            config,
+            bucket,
+            prefix,
+            client,
+        })
+    }
+
+    fn authorize(
+        &self,
+        builder: reqwest::blocking::RequestBuilder,
+    ) -> reqwest::blocking::RequestBuilder {
+        match (&self.config.access_key, &self.config.secret_key) {
+            (Some(key), Some(secret)) => builder.basic_auth(key, Some(secret)),
+            _ => builder,
+        }
+    }
+}
+
+impl PdfSource for S3PdfSource {
+    fn list(&self) -> Result<Vec<SourceRef>> {
+        let url = format!(
+            "{}/{}?list-type=2&prefix={}",
+            self.config.endpoint.trim_end_matches('/'),
+            self.bucket,
+            self.prefix
+        );
+        let response = self
+            .authorize(self.client.get(&url))
+            .send()
+            .with_context(|| format!("Failed to list s3://{}/{}", self.bucket, self.prefix))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Object listing failed ({}) for s3://{}/{}",
+                response.status(),
+                self.bucket,
+                self.prefix
+            );
+        }
+
+        let body = response.text()?;
+        Ok(parse_list_keys(&body)
+            .into_iter()
+            .filter(|key| key.ends_with(".pdf"))
+            .map(|key| SourceRef::S3 {
+                bucket: self.bucket.clone(),
+                key,
+            })
+            .collect())
+    }
+
+    fn read(&self, entry: &SourceRef) -> Result<Vec<u8>> {
+        let (bucket, key) = match entry {
+            SourceRef::S3 { bucket, key } => (bucket, key),
+            _ => anyhow::bail!("S3PdfSource was handed a non-S3 entry"),
+        };
+
+        let url = format!(
+            "{}/{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            bucket,
+            key.trim_start_matches('/')
+        );
+        let response = self
+            .authorize(self.client.get(&url))
+            .send()
+            .with_context(|| format!("Failed to GET s3://{}/{}", bucket, key))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Object fetch failed ({}) for s3://{}/{}",
+                response.status(),
+                bucket,
+                key
+            );
+        }
+
+        Ok(response.bytes()?.to_vec())
+    }
+}
+
+/// Pulls `<Key>...</Key>` entries out of a ListObjectsV2 response without
+/// pulling in a full XML dependency.
+fn parse_list_keys(body: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("<Key>") {
+        let after_start = &rest[start + "<Key>".len()..];
+        if let Some(end) = after_start.find("</Key>") {
+            keys.push(after_start[..end].to_string());
+            rest = &after_start[end + "</Key>".len()..];
+        } else {
+            break;
+        }
+    }
+    keys
+}
+
+/// PDFs fetched over plain HTTP(S), one URL per non-blank, non-comment line
+/// of a manifest file fetched from `--pdf-path` itself.
+pub struct HttpPdfSource {
+    manifest_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpPdfSource {
+    pub fn new(manifest_url: String) -> Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(Self {
+            manifest_url,
+            client,
+        })
+    }
+}
+
+impl PdfSource for HttpPdfSource {
+    fn list(&self) -> Result<Vec<SourceRef>> {
+        let response = self
+            .client
+            .get(&self.manifest_url)
+            .send()
+            .with_context(|| format!("Failed to fetch manifest {}", self.manifest_url))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Manifest fetch failed ({}) for {}",
+                response.status(),
+                self.manifest_url
+            );
+        }
+
+        let body = response.text()?;
+        Ok(body
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| SourceRef::Http(l.to_string()))
+            .collect())
+    }
+
+    fn read(&self, entry: &SourceRef) -> Result<Vec<u8>> {
+        let url = match entry {
+            SourceRef::Http(url) => url,
+            _ => anyhow::bail!("HttpPdfSource was handed a non-HTTP entry"),
+        };
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .with_context(|| format!("Failed to GET {}", url))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Fetch failed ({}) for {}", response.status(), url);
+        }
+
+        Ok(response.bytes()?.to_vec())
+    }
+}
+
+/// Picks a `PdfSource` based on the `--pdf-path` scheme: `s3://bucket/prefix`
+/// lists a bucket, `http(s)://...` treats the URL as a manifest of PDF URLs
+/// (one per line), and anything else is a local file or directory.
+pub fn resolve_pdf_source(
+    pdf_path: &str,
+    s3_config: Option<S3SourceConfig>,
+) -> Result<Box<dyn PdfSource>> {
+    if let Some(rest) = pdf_path.strip_prefix("s3://") {
+        let mut parts = rest.splitn(2, '/');
+        let bucket = parts.next().unwrap_or("").to_string();
+        let prefix = parts.next().unwrap_or("").to_string();
+        let config = s3_config.ok_or_else(|| {
+            anyhow::anyhow!(
+                "s3://{}/{} requested but no [s3] section found in the Elasticsearch config",
+                bucket,
+                prefix
+            )
+        })?;
+        return Ok(Box::new(S3PdfSource::new(config, bucket, prefix)?));
+    }
+
+    if pdf_path.starts_with("http://") || pdf_path.starts_with("https://") {
+        return Ok(Box::new(HttpPdfSource::new(pdf_path.to_string())?));
+    }
+
+    Ok(Box::new(LocalPdfSource::new(pdf_path)))
+}